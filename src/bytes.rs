@@ -0,0 +1,122 @@
+//! Byte-oriented parsers for ASCII protocols over `&[u8]` input.
+//!
+//! These mirror the character classes in the `text` module without requiring
+//! the input to be valid UTF-8. The module is not glob re-exported, so use
+//! `simons_parser::bytes::tag` and friends by path.
+
+use crate::{ParseError, ParseResult, Parser, combinators::*};
+
+pub mod number;
+
+/// Parse an ASCII digit byte
+/// Composed using the satisfy_labeled combinator
+pub fn digit_b<'a>() -> impl Parser<&'a [u8], u8> {
+    satisfy_labeled("ASCII digit", |b: &u8| b.is_ascii_digit())
+}
+
+/// Parse an ASCII alphabetic byte
+/// Composed using the satisfy_labeled combinator
+pub fn alpha_b<'a>() -> impl Parser<&'a [u8], u8> {
+    satisfy_labeled("ASCII letter", |b: &u8| b.is_ascii_alphabetic())
+}
+
+/// Parse an ASCII whitespace byte
+/// Composed using the satisfy_labeled combinator
+pub fn space_b<'a>() -> impl Parser<&'a [u8], u8> {
+    satisfy_labeled("ASCII whitespace", |b: &u8| b.is_ascii_whitespace())
+}
+
+/// Parse any byte from a given set, built once into a 256-bit bitmap
+/// Matching is a constant-time lookup however many bytes the set holds.
+pub fn byte_set(bytes: &[u8]) -> ByteSet {
+    let mut bits = [0u64; 4];
+    for &b in bytes {
+        bits[usize::from(b / 64)] |= 1 << (b % 64);
+    }
+    ByteSet {
+        bits,
+        bytes: bytes.to_vec(),
+    }
+}
+
+pub struct ByteSet {
+    bits: [u64; 4],
+    bytes: Vec<u8>,
+}
+
+impl<'a> Parser<&'a [u8], u8> for ByteSet {
+    fn parse(&self, input: &'a [u8]) -> ParseResult<&'a [u8], u8> {
+        match input.split_first() {
+            Some((&b, remaining)) if self.bits[usize::from(b / 64)] & (1 << (b % 64)) != 0 => {
+                Ok((b, remaining))
+            }
+            Some((b, _)) => Err(ParseError::expected(
+                format!("one of '{}'", self.bytes.escape_ascii()),
+                Some(format!("byte '{}'", b.escape_ascii())),
+                input,
+            )),
+            None => Err(ParseError::incomplete(Some(1))),
+        }
+    }
+}
+
+/// Parse a specific byte sequence
+/// Returns a slice of the input, so no allocation is performed
+pub fn tag(expected: &[u8]) -> Tag {
+    Tag {
+        expected: expected.to_vec(),
+        case_insensitive: false,
+    }
+}
+
+/// Parse a byte sequence, ignoring ASCII case
+/// Returns the matched slice of the input, in its original case
+pub fn ascii_ci(expected: &[u8]) -> Tag {
+    Tag {
+        expected: expected.to_vec(),
+        case_insensitive: true,
+    }
+}
+
+pub struct Tag {
+    expected: Vec<u8>,
+    case_insensitive: bool,
+}
+
+impl Tag {
+    fn matches(&self, expected: u8, actual: u8) -> bool {
+        if self.case_insensitive {
+            expected.eq_ignore_ascii_case(&actual)
+        } else {
+            expected == actual
+        }
+    }
+}
+
+impl<'a> Parser<&'a [u8], &'a [u8]> for Tag {
+    fn parse(&self, input: &'a [u8]) -> ParseResult<&'a [u8], &'a [u8]> {
+        let mismatch = input
+            .iter()
+            .zip(&self.expected)
+            .find(|(actual, expected)| !self.matches(**expected, **actual));
+        match mismatch {
+            Some((actual, _)) => Err(ParseError::expected(
+                format!(
+                    "tag '{}'{}",
+                    self.expected.escape_ascii(),
+                    if self.case_insensitive {
+                        " (case-insensitive)"
+                    } else {
+                        ""
+                    }
+                ),
+                Some(format!("byte '{}'", actual.escape_ascii())),
+                input,
+            )),
+            None if input.len() < self.expected.len() => Err(ParseError::incomplete(Some(
+                self.expected.len() - input.len(),
+            ))),
+            None => Ok(input.split_at(self.expected.len())),
+        }
+    }
+}
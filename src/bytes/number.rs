@@ -0,0 +1,41 @@
+//! Fixed-width binary integers over `&[u8]` input, in big- and little-endian order.
+//!
+//! A number is a fixed-size frame, so input too short to hold one fails with
+//! `UnexpectedEof` rather than the `Incomplete` of the streaming byte parsers.
+
+use crate::{ParseError, ParseResult, Parser};
+
+/// Split off the next `N` bytes as an array
+struct Array<const N: usize>;
+
+impl<'a, const N: usize> Parser<&'a [u8], [u8; N]> for Array<N> {
+    fn parse(&self, input: &'a [u8]) -> ParseResult<&'a [u8], [u8; N]> {
+        match input.split_first_chunk::<N>() {
+            Some((bytes, remaining)) => Ok((*bytes, remaining)),
+            None => Err(ParseError::UnexpectedEof),
+        }
+    }
+}
+
+macro_rules! int_parsers {
+    ($($be:ident, $le:ident => $ty:ty;)+) => {$(
+        #[doc = concat!("Parse a big-endian `", stringify!($ty), "`")]
+        pub fn $be<'a>() -> impl Parser<&'a [u8], $ty> {
+            Array::<{ size_of::<$ty>() }>.map(<$ty>::from_be_bytes)
+        }
+
+        #[doc = concat!("Parse a little-endian `", stringify!($ty), "`")]
+        pub fn $le<'a>() -> impl Parser<&'a [u8], $ty> {
+            Array::<{ size_of::<$ty>() }>.map(<$ty>::from_le_bytes)
+        }
+    )+};
+}
+
+int_parsers! {
+    be_u16, le_u16 => u16;
+    be_u32, le_u32 => u32;
+    be_u64, le_u64 => u64;
+    be_i16, le_i16 => i16;
+    be_i32, le_i32 => i32;
+    be_i64, le_i64 => i64;
+}
@@ -1,7 +1,13 @@
 //! Basic parsing primitives and utility combinators.
 
+use crate::input::{Positioned, Span, made_progress};
+use crate::parser::{fold_many0, repeat_bounded};
 use crate::{Input, ParseError, ParseResult, Parser};
+use std::borrow::Borrow;
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
 use std::marker::PhantomData;
+use std::rc::Rc;
 
 /// Consumes any single item from the input
 pub fn item<I: Input>() -> Item<I> {
@@ -16,27 +22,43 @@ pub struct Item<I> {
 
 impl<I: Input> Parser<I, I::Item> for Item<I> {
     fn parse(&self, input: I) -> ParseResult<I, I::Item> {
+        self.parse_or_restore(input).map_err(|(err, _)| err)
+    }
+
+    fn parse_or_restore(&self, input: I) -> Result<(I::Item, I), (ParseError<I>, I)> {
         match input.uncons() {
             Some((item, remaining)) => Ok((item, remaining)),
-            None => Err(ParseError::UnexpectedEof),
+            None => Err((ParseError::incomplete(Some(1)), input)),
         }
     }
 }
 
 /// Parses an item that satisfies the given predicate
 pub fn satisfy<I, F>(predicate: F) -> Satisfy<I, F>
+where
+    I: Input,
+    F: Fn(&I::Item) -> bool,
+{
+    satisfy_labeled("item satisfying predicate", predicate)
+}
+
+/// Parses an item that satisfies the given predicate, naming it `name` in errors
+/// E.g. `satisfy_labeled("hex digit", char::is_ascii_hexdigit)`
+pub fn satisfy_labeled<I, F>(name: impl Into<String>, predicate: F) -> Satisfy<I, F>
 where
     I: Input,
     F: Fn(&I::Item) -> bool,
 {
     Satisfy {
         predicate,
+        expected: name.into(),
         _phantom: PhantomData,
     }
 }
 
 pub struct Satisfy<I, F> {
     predicate: F,
+    expected: String,
     _phantom: PhantomData<I>,
 }
 
@@ -46,48 +68,265 @@ where
     F: Fn(&I::Item) -> bool,
 {
     fn parse(&self, input: I) -> ParseResult<I, I::Item> {
+        self.parse_or_restore(input).map_err(|(err, _)| err)
+    }
+
+    fn parse_or_restore(&self, input: I) -> Result<(I::Item, I), (ParseError<I>, I)> {
         match input.uncons() {
             Some((item, remaining)) => {
                 if (self.predicate)(&item) {
                     Ok((item, remaining))
                 } else {
-                    Err(ParseError::expected(
+                    let err = ParseError::expected(
+                        self.expected.as_str(),
+                        Some(format!("{:?}", item)),
+                        input.clone(),
+                    );
+                    Err((err, input))
+                }
+            }
+            None => Err((ParseError::incomplete(Some(1)), input)),
+        }
+    }
+}
+
+/// Parses an item and transforms it in one step, failing when `f` returns None
+pub fn satisfy_map<I, F, U>(f: F) -> SatisfyMap<I, F>
+where
+    I: Input,
+    F: Fn(&I::Item) -> Option<U>,
+{
+    SatisfyMap {
+        f,
+        _phantom: PhantomData,
+    }
+}
+
+pub struct SatisfyMap<I, F> {
+    f: F,
+    _phantom: PhantomData<I>,
+}
+
+impl<I, F, U> Parser<I, U> for SatisfyMap<I, F>
+where
+    I: Input,
+    F: Fn(&I::Item) -> Option<U>,
+{
+    fn parse(&self, input: I) -> ParseResult<I, U> {
+        self.parse_or_restore(input).map_err(|(err, _)| err)
+    }
+
+    fn parse_or_restore(&self, input: I) -> Result<(U, I), (ParseError<I>, I)> {
+        match input.uncons() {
+            Some((item, remaining)) => match (self.f)(&item) {
+                Some(value) => Ok((value, remaining)),
+                None => {
+                    let err = ParseError::expected(
                         "item satisfying predicate",
-                        Some("different item"),
-                        input,
-                    ))
+                        Some(format!("{:?}", item)),
+                        input.clone(),
+                    );
+                    Err((err, input))
                 }
+            },
+            None => Err((ParseError::incomplete(Some(1)), input)),
+        }
+    }
+}
+
+/// Checks that the next item satisfies the predicate without consuming it
+/// A guard like `peek(satisfy(...))` that discards the item and succeeds with `()`
+pub fn assert_next<I, F>(predicate: F) -> AssertNext<I, F>
+where
+    I: Input,
+    F: Fn(&I::Item) -> bool,
+{
+    AssertNext {
+        predicate,
+        _phantom: PhantomData,
+    }
+}
+
+pub struct AssertNext<I, F> {
+    predicate: F,
+    _phantom: PhantomData<I>,
+}
+
+impl<I, F> Parser<I, ()> for AssertNext<I, F>
+where
+    I: Input,
+    F: Fn(&I::Item) -> bool,
+{
+    fn parse(&self, input: I) -> ParseResult<I, ()> {
+        match input.uncons() {
+            Some((item, _)) if (self.predicate)(&item) => Ok(((), input)),
+            Some((item, _)) => Err(ParseError::expected(
+                "item satisfying predicate",
+                Some(format!("{:?}", item)),
+                input,
+            )),
+            None => Err(ParseError::incomplete(Some(1))),
+        }
+    }
+}
+
+/// Parses any item contained in the given set
+/// Composed using the satisfy_labeled combinator
+pub fn one_of_slice<I: Input>(items: &[I::Item]) -> impl Parser<I, I::Item> + use<I> {
+    let items = items.to_vec();
+    satisfy_labeled(format!("one of {:?}", items), move |item: &I::Item| {
+        items.contains(item)
+    })
+}
+
+/// Parses any item not contained in the given set
+/// Composed using the satisfy_labeled combinator
+pub fn none_of_slice<I: Input>(items: &[I::Item]) -> impl Parser<I, I::Item> + use<I> {
+    let items = items.to_vec();
+    satisfy_labeled(format!("none of {:?}", items), move |item: &I::Item| {
+        !items.contains(item)
+    })
+}
+
+/// Consumes exactly `n` items, returning them as a sub-input
+/// Needs an input that implements `Input::uncons_n`, as every built-in input does.
+pub fn take<I: Input>(n: usize) -> Take<I> {
+    Take {
+        count: n,
+        _phantom: PhantomData,
+    }
+}
+
+pub struct Take<I> {
+    count: usize,
+    _phantom: PhantomData<I>,
+}
+
+impl<I: Input> Parser<I, I> for Take<I> {
+    fn parse(&self, input: I) -> ParseResult<I, I> {
+        input.uncons_n(self.count).ok_or_else(|| {
+            let available =
+                std::iter::successors(input.uncons(), |(_, remaining)| remaining.uncons()).count();
+            match self.count.checked_sub(available) {
+                Some(needed) if needed > 0 => ParseError::incomplete(Some(needed)),
+                _ => ParseError::message("input does not support taking a prefix", input.clone()),
             }
-            None => Err(ParseError::UnexpectedEof),
+        })
+    }
+}
+
+/// Consumes the entire remaining input, returning it as a sub-input
+/// Always succeeds, leaving an empty input behind
+pub fn rest<I: Input>() -> Rest<I> {
+    Rest {
+        _phantom: PhantomData,
+    }
+}
+
+pub struct Rest<I> {
+    _phantom: PhantomData<I>,
+}
+
+impl<I: Input> Parser<I, I> for Rest<I> {
+    fn parse(&self, input: I) -> ParseResult<I, I> {
+        let mut end = input.clone();
+        while let Some((_, remaining)) = end.uncons() {
+            end = remaining;
         }
+        Ok((input, end))
     }
 }
 
 /// Parses a specific item
 pub fn token<I: Input>(expected: I::Item) -> Token<I> {
-    Token { expected }
+    Token {
+        expected,
+        _phantom: PhantomData,
+    }
 }
 
-pub struct Token<I: Input> {
-    expected: I::Item,
+/// Matches one item equal to `expected`, which may be owned or borrowed
+pub struct Token<I: Input, B = <I as Input>::Item> {
+    expected: B,
+    _phantom: PhantomData<I>,
 }
 
-impl<I: Input> Parser<I, I::Item> for Token<I> {
+impl<I, B> Parser<I, I::Item> for Token<I, B>
+where
+    I: Input,
+    B: Borrow<I::Item>,
+{
     fn parse(&self, input: I) -> ParseResult<I, I::Item> {
+        self.parse_or_restore(input).map_err(|(err, _)| err)
+    }
+
+    fn parse_or_restore(&self, input: I) -> Result<(I::Item, I), (ParseError<I>, I)> {
+        let expected = self.expected.borrow();
         match input.uncons() {
             Some((item, remaining)) => {
-                if item == self.expected {
+                if item == *expected {
                     Ok((item, remaining))
                 } else {
-                    Err(ParseError::expected(
-                        format!("{:?}", self.expected),
+                    let err = ParseError::expected(
+                        format!("{:?}", expected),
+                        Some(format!("{:?}", item)),
+                        input.clone(),
+                    );
+                    Err((err, input))
+                }
+            }
+            None => Err((ParseError::incomplete(Some(1)), input)),
+        }
+    }
+}
+
+/// Parses a specific item, borrowing it rather than taking ownership
+/// Useful when `Item` is large, such as an enum carrying data, and already owned elsewhere.
+pub fn token_ref<I: Input>(expected: &I::Item) -> Token<I, &I::Item> {
+    Token {
+        expected,
+        _phantom: PhantomData,
+    }
+}
+
+/// Parses a specific sequence of items, the multi-item form of `token`
+///
+/// A mismatch reports the position within the sequence where the input diverged,
+/// with the error pointing at the start of the sequence. Running out of input
+/// part-way is `Incomplete`. Named `tokens` rather than `tag` so it does not clash
+/// with `bytes::tag`, which returns a borrowed slice instead.
+pub fn tokens<I: Input>(expected: &[I::Item]) -> Tokens<I> {
+    Tokens {
+        expected: expected.to_vec(),
+    }
+}
+
+pub struct Tokens<I: Input> {
+    expected: Vec<I::Item>,
+}
+
+impl<I: Input> Parser<I, Vec<I::Item>> for Tokens<I> {
+    fn parse(&self, input: I) -> ParseResult<I, Vec<I::Item>> {
+        let mut remaining = input.clone();
+        for (position, expected) in self.expected.iter().enumerate() {
+            match remaining.uncons() {
+                Some((item, rest)) if item == *expected => remaining = rest,
+                Some((item, _)) => {
+                    return Err(ParseError::expected(
+                        format!(
+                            "{:?} at position {} of {:?}",
+                            expected, position, self.expected
+                        ),
                         Some(format!("{:?}", item)),
                         input,
-                    ))
+                    ));
+                }
+                None => {
+                    return Err(ParseError::incomplete(Some(self.expected.len() - position)));
                 }
             }
-            None => Err(ParseError::UnexpectedEof),
         }
+        Ok((self.expected.clone(), remaining))
     }
 }
 
@@ -109,6 +348,40 @@ where
     parser.preceded_by(left).skip(right)
 }
 
+/// Parse `prefix` then `parser`, keeping only the result of `parser`
+/// Named after nom's `preceded`; composed using preceded_by
+pub fn preceded<I, F, P, T, U>(prefix: F, parser: P) -> impl Parser<I, U>
+where
+    I: Input,
+    F: Parser<I, T>,
+    P: Parser<I, U>,
+{
+    parser.preceded_by(prefix)
+}
+
+/// Parse `parser` then `suffix`, keeping only the result of `parser`
+/// Named after nom's `terminated`; composed using skip
+pub fn terminated<I, P, S, T, U>(parser: P, suffix: S) -> impl Parser<I, T>
+where
+    I: Input,
+    P: Parser<I, T>,
+    S: Parser<I, U>,
+{
+    parser.skip(suffix)
+}
+
+/// Parse `parser` between `open` and `close`, keeping only the result of `parser`
+/// Named after nom's `delimited`; composed using between
+pub fn delimited<I, L, P, R, T, U, V>(open: L, parser: P, close: R) -> impl Parser<I, U>
+where
+    I: Input,
+    L: Parser<I, T>,
+    P: Parser<I, U>,
+    R: Parser<I, V>,
+{
+    between(open, parser, close)
+}
+
 /// Choice between multiple parsers (tries each in order)
 pub fn choice<I: Input, T, P: Parser<I, T>>(parsers: Vec<P>) -> Choice<I, T, P> {
     Choice {
@@ -128,145 +401,1171 @@ where
     P: Parser<I, T>,
 {
     fn parse(&self, input: I) -> ParseResult<I, T> {
-        let mut errors = Vec::new();
+        parse_first(&self.parsers, input).map(|(_, result)| result)
+    }
+}
 
-        for parser in &self.parsers {
-            match parser.parse(input.clone()) {
-                Ok(result) => return Ok(result),
-                Err(err) => errors.push(err),
-            }
+/// Helper function trying each parser in order, returning the index of the first
+/// success along with its result (shared logic)
+fn parse_first<I, T, P>(parsers: &[P], input: I) -> Result<(usize, (T, I)), ParseError<I>>
+where
+    I: Input,
+    P: Parser<I, T>,
+{
+    let Some((last, init)) = parsers.split_last() else {
+        return Err(ParseError::many(Vec::new()));
+    };
+    let mut errors = Vec::with_capacity(parsers.len());
+
+    for (index, parser) in init.iter().enumerate() {
+        match parser.parse(input.clone()) {
+            Ok(result) => return Ok((index, result)),
+            Err(err) => errors.push(err),
+        }
+    }
+
+    // The last alternative has nothing to backtrack to, so it takes the input
+    match last.parse(input) {
+        Ok(result) => Ok((init.len(), result)),
+        Err(err) => {
+            errors.push(err);
+            Err(ParseError::many(errors))
         }
+    }
+}
 
-        Err(ParseError::many(errors))
+/// Runs every parser in order, collecting their results
+/// The counterpart to `choice`: all must succeed, and the first failure is returned.
+pub fn sequence<I: Input, T, P: Parser<I, T>>(parsers: Vec<P>) -> Sequence<I, T, P> {
+    Sequence {
+        parsers,
+        _phantom: PhantomData,
     }
 }
 
-/// Helper function for parsing separated items (shared logic)
-fn parse_sep_by_impl<I, P, S, T, U>(
-    parser: &P,
-    separator: &S,
-    first: T,
-    mut remaining: I,
-) -> ParseResult<I, Vec<T>>
+pub struct Sequence<I, T, P> {
+    parsers: Vec<P>,
+    _phantom: PhantomData<(I, T)>,
+}
+
+impl<I, T, P> Parser<I, Vec<T>> for Sequence<I, T, P>
 where
     I: Input,
     P: Parser<I, T>,
-    S: Parser<I, U>,
-    T: Clone,
 {
-    let mut results = vec![first];
-
-    // Parse separator followed by element, repeatedly
-    loop {
-        let input_before_sep = remaining.clone();
-        match separator.parse(remaining.clone()) {
-            Ok((_, after_sep)) => match parser.parse(after_sep) {
-                Ok((element, after_element)) => {
-                    results.push(element);
-                    remaining = after_element;
-                }
-                Err(_) => {
-                    // Separator without following element - backtrack
-                    remaining = input_before_sep;
-                    break;
-                }
-            },
-            Err(_) => break, // No more separators
+    fn parse(&self, mut input: I) -> ParseResult<I, Vec<T>> {
+        let mut results = Vec::with_capacity(self.parsers.len());
+        for parser in &self.parsers {
+            let (result, remaining) = parser.parse(input)?;
+            results.push(result);
+            input = remaining;
         }
+        Ok((results, input))
     }
+}
 
-    Ok((results, remaining))
+/// Choice between multiple parsers, also returning the index of the one that matched
+pub fn choice_indexed<I: Input, T, P: Parser<I, T>>(parsers: Vec<P>) -> ChoiceIndexed<I, T, P> {
+    ChoiceIndexed {
+        parsers,
+        _phantom: PhantomData,
+    }
 }
 
-/// Parses items separated by a delimiter
-pub fn sep_by<I, P, S, T, U>(parser: P, separator: S) -> SepBy<P, S, T, U>
+pub struct ChoiceIndexed<I, T, P> {
+    parsers: Vec<P>,
+    _phantom: PhantomData<(I, T)>,
+}
+
+impl<I, T, P> Parser<I, (usize, T)> for ChoiceIndexed<I, T, P>
 where
     I: Input,
     P: Parser<I, T>,
-    S: Parser<I, U>,
-    T: Clone,
 {
-    SepBy {
-        parser,
-        separator,
+    fn parse(&self, input: I) -> ParseResult<I, (usize, T)> {
+        parse_first(&self.parsers, input)
+            .map(|(index, (result, remaining))| ((index, result), remaining))
+    }
+}
+
+/// Runs every parser and keeps the result of the one that consumed the most input
+///
+/// Maximal munch for lexers, so `<=` wins over `<` regardless of order. Ties go to
+/// the earliest alternative, as do all successes on inputs that do not report a
+/// `len`. If every alternative fails, their errors are collected into `Many`.
+pub fn longest<I: Input, T, P: Parser<I, T>>(parsers: Vec<P>) -> Longest<I, T, P> {
+    Longest {
+        parsers,
         _phantom: PhantomData,
     }
 }
 
-pub struct SepBy<P, S, T, U> {
-    parser: P,
-    separator: S,
-    _phantom: PhantomData<(T, U)>,
+pub struct Longest<I, T, P> {
+    parsers: Vec<P>,
+    _phantom: PhantomData<(I, T)>,
 }
 
-impl<I, P, S, T, U> Parser<I, Vec<T>> for SepBy<P, S, T, U>
+impl<I, T, P> Parser<I, T> for Longest<I, T, P>
 where
     I: Input,
     P: Parser<I, T>,
-    S: Parser<I, U>,
-    T: Clone,
 {
-    fn parse(&self, input: I) -> ParseResult<I, Vec<T>> {
-        // Try to parse the first element
-        match self.parser.parse(input.clone()) {
-            Ok((first, remaining)) => {
-                parse_sep_by_impl(&self.parser, &self.separator, first, remaining)
+    fn parse(&self, input: I) -> ParseResult<I, T> {
+        let mut best: Option<(T, I)> = None;
+        let mut errors = Vec::new();
+        for parser in &self.parsers {
+            match parser.parse(input.clone()) {
+                Ok((result, remaining)) => {
+                    let longer = best.as_ref().is_none_or(|(_, best_remaining)| {
+                        matches!(
+                            (remaining.len(), best_remaining.len()),
+                            (Some(len), Some(best_len)) if len < best_len
+                        )
+                    });
+                    if longer {
+                        best = Some((result, remaining));
+                    }
+                }
+                Err(err) => errors.push(err),
             }
-            Err(_) => Ok((Vec::new(), input)), // Empty list is valid
         }
+        best.ok_or_else(|| ParseError::many(errors))
     }
 }
 
-/// Parse one or more items separated by a delimiter
-pub fn sep_by1<I, P, S, T, U>(parser: P, separator: S) -> SepBy1<P, S, T, U>
+/// Count zero or more occurrences of a parser, without collecting the results
+/// Composed using fold_many0
+pub fn count_of<I, T, P>(parser: P) -> impl Parser<I, usize>
 where
     I: Input,
     P: Parser<I, T>,
-    S: Parser<I, U>,
-    T: Clone,
 {
-    SepBy1 {
-        parser,
-        separator,
+    parser.fold_many0(0, |count, _| count + 1)
+}
+
+/// Count one or more occurrences of a parser, failing if there are none
+/// Composed using fold_many1
+pub fn count_of1<I, T, P>(parser: P) -> impl Parser<I, usize>
+where
+    I: Input,
+    P: Parser<I, T>,
+{
+    parser.fold_many1(0, |count, _| count + 1)
+}
+
+/// Parse a count, then exactly that many items, as in length-prefixed binary formats
+///
+/// The count may be any integer type convertible to `usize`; one that is not, such
+/// as a negative number, fails with "count out of range" at the count. Running out
+/// of input before the last item fails with that item's error.
+pub fn length_count<I, N, T, C, P>(count: C, item: P) -> LengthCount<C, P, N>
+where
+    I: Input,
+    N: TryInto<usize>,
+    C: Parser<I, N>,
+    P: Parser<I, T>,
+{
+    LengthCount {
+        count,
+        item,
         _phantom: PhantomData,
     }
 }
 
-pub struct SepBy1<P, S, T, U> {
-    parser: P,
-    separator: S,
-    _phantom: PhantomData<(T, U)>,
+pub struct LengthCount<C, P, N> {
+    count: C,
+    item: P,
+    _phantom: PhantomData<N>,
 }
 
-impl<I, P, S, T, U> Parser<I, Vec<T>> for SepBy1<P, S, T, U>
+impl<I, N, T, C, P> Parser<I, Vec<T>> for LengthCount<C, P, N>
 where
     I: Input,
+    N: TryInto<usize>,
+    C: Parser<I, N>,
     P: Parser<I, T>,
-    S: Parser<I, U>,
-    T: Clone,
 {
     fn parse(&self, input: I) -> ParseResult<I, Vec<T>> {
-        let (first, remaining) = self.parser.parse(input)?;
-        parse_sep_by_impl(&self.parser, &self.separator, first, remaining)
+        let (count, remaining) = parse_count(&self.count, input)?;
+        repeat_bounded(&self.item, count, Some(count), remaining)
     }
 }
 
-/// Parses end of input
-pub fn eof<I: Input>() -> Eof<I> {
-    Eof {
+/// Parse a length prefix as a `usize`, shared by the `length_*` combinators
+fn parse_count<I, N, C>(count: &C, input: I) -> ParseResult<I, usize>
+where
+    I: Input,
+    N: TryInto<usize>,
+    C: Parser<I, N>,
+{
+    let (count, remaining) = count.parse(input.clone())?;
+    match count.try_into() {
+        Ok(count) => Ok((count, remaining)),
+        Err(_) => Err(ParseError::message("count out of range", input)),
+    }
+}
+
+/// Parse a length, then return that many following items as a sub-input
+/// Composed using take, so running out of input is `Incomplete`
+pub fn length_data<I, N, C>(count: C) -> LengthData<C, N>
+where
+    I: Input,
+    N: TryInto<usize>,
+    C: Parser<I, N>,
+{
+    LengthData {
+        count,
         _phantom: PhantomData,
     }
 }
 
-pub struct Eof<I> {
-    _phantom: PhantomData<I>,
+pub struct LengthData<C, N> {
+    count: C,
+    _phantom: PhantomData<N>,
 }
 
-impl<I: Input> Parser<I, ()> for Eof<I> {
-    fn parse(&self, input: I) -> ParseResult<I, ()> {
-        if input.is_empty() {
-            Ok(((), input))
-        } else {
+impl<I, N, C> Parser<I, I> for LengthData<C, N>
+where
+    I: Input,
+    N: TryInto<usize>,
+    C: Parser<I, N>,
+{
+    fn parse(&self, input: I) -> ParseResult<I, I> {
+        let (count, remaining) = parse_count(&self.count, input)?;
+        take(count).parse(remaining)
+    }
+}
+
+/// Parse a length, then run `value` over exactly that many following items
+///
+/// `value` must consume the whole frame, so a field cannot overrun into or stop
+/// short of the data after it, as in a type-length-value record. Errors from
+/// `value` point into the frame, and since the frame is complete, running out of
+/// it is `UnexpectedEof` rather than `Incomplete`.
+pub fn length_value<I, N, T, C, P>(count: C, value: P) -> LengthValue<C, P, N>
+where
+    I: Input,
+    N: TryInto<usize>,
+    C: Parser<I, N>,
+    P: Parser<I, T>,
+{
+    LengthValue {
+        count,
+        value,
+        _phantom: PhantomData,
+    }
+}
+
+pub struct LengthValue<C, P, N> {
+    count: C,
+    value: P,
+    _phantom: PhantomData<N>,
+}
+
+impl<I, N, T, C, P> Parser<I, T> for LengthValue<C, P, N>
+where
+    I: Input,
+    N: TryInto<usize>,
+    C: Parser<I, N>,
+    P: Parser<I, T>,
+{
+    fn parse(&self, input: I) -> ParseResult<I, T> {
+        let (count, remaining) = parse_count(&self.count, input)?;
+        let (frame, remaining) = take(count).parse(remaining)?;
+        let (value, leftover) = self.value.parse(frame).map_err(ParseError::into_complete)?;
+        if !leftover.is_empty() {
+            return Err(ParseError::message("unexpected trailing input", leftover));
+        }
+        Ok((value, remaining))
+    }
+}
+
+/// Parse zero or more occurrences, keeping only the results that `f` maps to `Some`
+pub fn many_filter_map<I, T, U, P, F>(parser: P, f: F) -> ManyFilterMap<P, F, T>
+where
+    I: Input,
+    P: Parser<I, T>,
+    F: Fn(T) -> Option<U>,
+{
+    ManyFilterMap {
+        parser,
+        f,
+        _phantom: PhantomData,
+    }
+}
+
+pub struct ManyFilterMap<P, F, T> {
+    parser: P,
+    f: F,
+    _phantom: PhantomData<T>,
+}
+
+impl<I, T, U, P, F> Parser<I, Vec<U>> for ManyFilterMap<P, F, T>
+where
+    I: Input,
+    P: Parser<I, T>,
+    F: Fn(T) -> Option<U>,
+{
+    fn parse(&self, input: I) -> ParseResult<I, Vec<U>> {
+        fold_many0(
+            &self.parser,
+            Vec::new(),
+            |mut items, item| {
+                items.extend((self.f)(item));
+                items
+            },
+            input,
+        )
+    }
+}
+
+/// Try a tuple of differently-typed parsers in order, returning the first success
+/// All alternatives must produce the same output type. On failure the errors of
+/// every alternative are collected into `ParseError::Many`, as with `choice()`.
+pub fn alt<I: Input, T, A: Alternatives<I, T>>(alternatives: A) -> Alt<I, T, A> {
+    Alt {
+        alternatives,
+        _phantom: PhantomData,
+    }
+}
+
+pub struct Alt<I, T, A> {
+    alternatives: A,
+    _phantom: PhantomData<(I, T)>,
+}
+
+impl<I, T, A> Parser<I, T> for Alt<I, T, A>
+where
+    I: Input,
+    A: Alternatives<I, T>,
+{
+    fn parse(&self, input: I) -> ParseResult<I, T> {
+        self.alternatives.parse_alternatives(input)
+    }
+}
+
+/// A tuple of parsers producing the same output type, usable with `alt()`
+/// Implemented for tuples of 2 to 12 parsers.
+pub trait Alternatives<I: Input, T> {
+    fn parse_alternatives(&self, input: I) -> ParseResult<I, T>;
+}
+
+macro_rules! impl_alternatives {
+    ($($parser:ident $index:tt),+) => {
+        impl<I: Input, T, $($parser: Parser<I, T>),+> Alternatives<I, T> for ($($parser,)+) {
+            fn parse_alternatives(&self, input: I) -> ParseResult<I, T> {
+                let mut errors = Vec::new();
+                $(
+                    match self.$index.parse(input.clone()) {
+                        Ok(result) => return Ok(result),
+                        Err(err) => errors.push(err),
+                    }
+                )+
+                Err(ParseError::many(errors))
+            }
+        }
+    };
+}
+
+impl_alternatives!(P0 0, P1 1);
+impl_alternatives!(P0 0, P1 1, P2 2);
+impl_alternatives!(P0 0, P1 1, P2 2, P3 3);
+impl_alternatives!(P0 0, P1 1, P2 2, P3 3, P4 4);
+impl_alternatives!(P0 0, P1 1, P2 2, P3 3, P4 4, P5 5);
+impl_alternatives!(P0 0, P1 1, P2 2, P3 3, P4 4, P5 5, P6 6);
+impl_alternatives!(P0 0, P1 1, P2 2, P3 3, P4 4, P5 5, P6 6, P7 7);
+impl_alternatives!(P0 0, P1 1, P2 2, P3 3, P4 4, P5 5, P6 6, P7 7, P8 8);
+impl_alternatives!(P0 0, P1 1, P2 2, P3 3, P4 4, P5 5, P6 6, P7 7, P8 8, P9 9);
+impl_alternatives!(P0 0, P1 1, P2 2, P3 3, P4 4, P5 5, P6 6, P7 7, P8 8, P9 9, P10 10);
+impl_alternatives!(P0 0, P1 1, P2 2, P3 3, P4 4, P5 5, P6 6, P7 7, P8 8, P9 9, P10 10, P11 11);
+
+/// Helper function for folding over separated items after the first (shared logic)
+fn fold_separated<I, P, S, T, U, A, F>(
+    parser: &P,
+    separator: &S,
+    mut acc: A,
+    mut f: F,
+    mut remaining: I,
+) -> ParseResult<I, A>
+where
+    I: Input,
+    P: Parser<I, T>,
+    S: Parser<I, U>,
+    F: FnMut(A, U, T) -> A,
+{
+    // Parse separator followed by element, repeatedly. `remaining` is only
+    // replaced once both succeed, so backtracking needs no extra copy.
+    while let Ok((sep, after_sep)) = separator.parse(remaining.clone()) {
+        match parser.parse(after_sep) {
+            Ok((element, after_element)) => {
+                acc = f(acc, sep, element);
+                remaining = after_element;
+            }
+            Err(_) => break, // Separator without following element - backtrack
+        }
+    }
+
+    Ok((acc, remaining))
+}
+
+/// Helper function for parsing separated items (shared logic)
+fn parse_sep_by_impl<I, P, S, T, U>(
+    parser: &P,
+    separator: &S,
+    first: T,
+    remaining: I,
+) -> ParseResult<I, Vec<T>>
+where
+    I: Input,
+    P: Parser<I, T>,
+    S: Parser<I, U>,
+{
+    fold_separated(
+        parser,
+        separator,
+        vec![first],
+        |mut acc, _, item| {
+            acc.push(item);
+            acc
+        },
+        remaining,
+    )
+}
+
+/// Parses items separated by a delimiter
+pub fn sep_by<I, P, S, T, U>(parser: P, separator: S) -> SepBy<P, S, T, U>
+where
+    I: Input,
+    P: Parser<I, T>,
+    S: Parser<I, U>,
+{
+    SepBy {
+        parser,
+        separator,
+        _phantom: PhantomData,
+    }
+}
+
+pub struct SepBy<P, S, T, U> {
+    parser: P,
+    separator: S,
+    _phantom: PhantomData<(T, U)>,
+}
+
+impl<I, P, S, T, U> Parser<I, Vec<T>> for SepBy<P, S, T, U>
+where
+    I: Input,
+    P: Parser<I, T>,
+    S: Parser<I, U>,
+{
+    fn parse(&self, input: I) -> ParseResult<I, Vec<T>> {
+        // Try to parse the first element
+        match self.parser.parse(input.clone()) {
+            Ok((first, remaining)) => {
+                parse_sep_by_impl(&self.parser, &self.separator, first, remaining)
+            }
+            Err(_) => Ok((Vec::new(), input)), // Empty list is valid
+        }
+    }
+}
+
+/// Parse one or more items separated by a delimiter
+pub fn sep_by1<I, P, S, T, U>(parser: P, separator: S) -> SepBy1<P, S, T, U>
+where
+    I: Input,
+    P: Parser<I, T>,
+    S: Parser<I, U>,
+{
+    SepBy1 {
+        parser,
+        separator,
+        _phantom: PhantomData,
+    }
+}
+
+pub struct SepBy1<P, S, T, U> {
+    parser: P,
+    separator: S,
+    _phantom: PhantomData<(T, U)>,
+}
+
+impl<I, P, S, T, U> Parser<I, Vec<T>> for SepBy1<P, S, T, U>
+where
+    I: Input,
+    P: Parser<I, T>,
+    S: Parser<I, U>,
+{
+    fn parse(&self, input: I) -> ParseResult<I, Vec<T>> {
+        let (first, remaining) = self.parser.parse(input)?;
+        parse_sep_by_impl(&self.parser, &self.separator, first, remaining)
+    }
+}
+
+/// Treats running out of input as a definitive failure
+///
+/// Primitives report `Incomplete` when they hit the end of input, since in
+/// streaming mode more input may arrive. Callers that already have the full
+/// input can wrap their parser in `complete` to get `UnexpectedEof` instead.
+pub fn complete<P>(parser: P) -> Complete<P> {
+    Complete { parser }
+}
+
+pub struct Complete<P> {
+    parser: P,
+}
+
+impl<I, T, P> Parser<I, T> for Complete<P>
+where
+    I: Input,
+    P: Parser<I, T>,
+{
+    fn parse(&self, input: I) -> ParseResult<I, T> {
+        self.parser.parse(input).map_err(ParseError::into_complete)
+    }
+}
+
+/// Packrat memoization: caches the result of a parser at each input position
+///
+/// Results are cached only while a `memo_scope` sharing the same `Memo` is running,
+/// and each run of that scope starts from an empty cache, so nothing leaks from one
+/// parse into the next. Positions are identified by `Input::position_key`; inputs
+/// without one are parsed without caching. Memoized parsers must be pure: the same
+/// input must always produce the same result. For recursive-descent grammars with
+/// heavy backtracking this turns exponential re-parsing into a single parse per
+/// position.
+pub fn memoize<I, T, P>(parser: P, memo: &Memo) -> Memoize<I, T, P>
+where
+    I: Input,
+    P: Parser<I, T>,
+    T: Clone,
+{
+    Memoize {
+        parser,
+        memo: memo.clone(),
+        cache: RefCell::new((0, HashMap::new())),
+    }
+}
+
+type MemoTable<I, T> = HashMap<(usize, usize, usize), ParseResult<I, T>>;
+
+pub struct Memoize<I, T, P> {
+    parser: P,
+    memo: Memo,
+    cache: RefCell<(u64, MemoTable<I, T>)>,
+}
+
+impl<I, T, P> Parser<I, T> for Memoize<I, T, P>
+where
+    I: Input,
+    P: Parser<I, T>,
+    T: Clone,
+{
+    fn parse(&self, input: I) -> ParseResult<I, T> {
+        let run = match self.memo.run.get() {
+            Some(run) => run,
+            None => return self.parser.parse(input),
+        };
+        let Some(key) = input.position_key() else {
+            return self.parser.parse(input);
+        };
+        {
+            let mut cache = self.cache.borrow_mut();
+            if cache.0 != run {
+                *cache = (run, HashMap::new());
+            }
+            if let Some(result) = cache.1.get(&key) {
+                return result.clone();
+            }
+        }
+        let result = self.parser.parse(input);
+        self.cache.borrow_mut().1.insert(key, result.clone());
+        result
+    }
+}
+
+/// The parse run that `memoize` caches belong to, shared by `memo_scope`
+///
+/// Clones share the same state, so one `Memo` can be captured by every memoized
+/// rule of a grammar.
+#[derive(Debug, Clone, Default)]
+pub struct Memo {
+    run: Rc<Cell<Option<u64>>>,
+    runs: Rc<Cell<u64>>,
+}
+
+impl Memo {
+    /// Create a memo context with no run in progress
+    pub fn new() -> Self {
+        Memo::default()
+    }
+}
+
+/// Run `parser` as one memoization run: `memoize` parsers sharing `memo` cache
+/// results until it returns, and start afresh on the next call
+///
+/// Wrap the outermost parser of the grammar. A scope entered while another run of
+/// the same `memo` is in progress joins that run.
+pub fn memo_scope<P>(parser: P, memo: &Memo) -> MemoScope<P> {
+    MemoScope {
+        parser,
+        memo: memo.clone(),
+    }
+}
+
+pub struct MemoScope<P> {
+    parser: P,
+    memo: Memo,
+}
+
+impl<I, T, P> Parser<I, T> for MemoScope<P>
+where
+    I: Input,
+    P: Parser<I, T>,
+{
+    fn parse(&self, input: I) -> ParseResult<I, T> {
+        if self.memo.run.get().is_some() {
+            return self.parser.parse(input);
+        }
+        let run = self.memo.runs.get() + 1;
+        self.memo.runs.set(run);
+        self.memo.run.set(Some(run));
+        let result = self.parser.parse(input);
+        self.memo.run.set(None);
+        result
+    }
+}
+
+/// A shared nesting counter with an upper bound, for use with `nested()`
+///
+/// Clones share the same counter, so one limit can be captured by every
+/// recursive entry point of a grammar.
+#[derive(Debug, Clone)]
+pub struct DepthLimit {
+    depth: Rc<Cell<usize>>,
+    max: usize,
+}
+
+impl DepthLimit {
+    /// Create a limit allowing at most `max` levels of nesting
+    pub fn new(max: usize) -> Self {
+        DepthLimit {
+            depth: Rc::new(Cell::new(0)),
+            max,
+        }
+    }
+
+    /// Number of `nested()` parsers currently running
+    pub fn depth(&self) -> usize {
+        self.depth.get()
+    }
+}
+
+/// Count one level of nesting around `parser`, failing with "maximum nesting depth
+/// exceeded" rather than recursing past the limit
+///
+/// Wrap the recursive step of a grammar (e.g. the contents of a bracket) so deeply
+/// nested untrusted input produces an error instead of overflowing the stack.
+pub fn nested<I, T, P>(parser: P, limit: &DepthLimit) -> Nested<P, T>
+where
+    I: Input,
+    P: Parser<I, T>,
+{
+    Nested {
+        parser,
+        limit: limit.clone(),
+        _phantom: PhantomData,
+    }
+}
+
+pub struct Nested<P, T> {
+    parser: P,
+    limit: DepthLimit,
+    _phantom: PhantomData<T>,
+}
+
+impl<I, T, P> Parser<I, T> for Nested<P, T>
+where
+    I: Input,
+    P: Parser<I, T>,
+{
+    fn parse(&self, input: I) -> ParseResult<I, T> {
+        let depth = self.limit.depth.get();
+        if depth >= self.limit.max {
+            return Err(ParseError::message("maximum nesting depth exceeded", input));
+        }
+        self.limit.depth.set(depth + 1);
+        let result = self.parser.parse(input);
+        self.limit.depth.set(depth);
+        result
+    }
+}
+
+/// Negative lookahead: succeeds without consuming input when `parser` fails, and
+/// fails when it succeeds
+pub fn not<P, T>(parser: P) -> Not<P, T> {
+    Not {
+        parser,
+        _phantom: PhantomData,
+    }
+}
+
+pub struct Not<P, T> {
+    parser: P,
+    _phantom: PhantomData<T>,
+}
+
+impl<I, T, P> Parser<I, ()> for Not<P, T>
+where
+    I: Input,
+    P: Parser<I, T>,
+{
+    fn parse(&self, input: I) -> ParseResult<I, ()> {
+        match self.parser.parse(input.clone()) {
+            Ok(_) => Err(ParseError::message("unexpected input", input)),
+            Err(_) => Ok(((), input)),
+        }
+    }
+}
+
+/// Positive lookahead: runs `parser` and returns its result without consuming input
+/// Failures are propagated unchanged.
+pub fn peek<P>(parser: P) -> Peek<P> {
+    Peek { parser }
+}
+
+pub struct Peek<P> {
+    parser: P,
+}
+
+impl<I, T, P> Parser<I, T> for Peek<P>
+where
+    I: Input,
+    P: Parser<I, T>,
+{
+    fn parse(&self, input: I) -> ParseResult<I, T> {
+        let (result, _) = self.parser.parse(input.clone())?;
+        Ok((result, input))
+    }
+}
+
+/// Records the span of input consumed by a parser alongside its result
+pub fn spanned<P>(parser: P) -> Spanned<P> {
+    Spanned { parser }
+}
+
+pub struct Spanned<P> {
+    parser: P,
+}
+
+impl<I, T, P> Parser<I, (T, Span)> for Spanned<P>
+where
+    I: Positioned,
+    P: Parser<I, T>,
+{
+    fn parse(&self, input: I) -> ParseResult<I, (T, Span)> {
+        let start = input.position();
+        let (result, remaining) = self.parser.parse(input)?;
+        let span = Span::new(start, remaining.position());
+        Ok(((result, span), remaining))
+    }
+}
+
+/// Parse between `min` and `max` items separated by a delimiter
+///
+/// Stops after `max` items without consuming a trailing separator, and fails if
+/// fewer than `min` items were found. With `min == 0` an empty list is valid.
+pub fn sep_by_m_n<I, P, S, T, U>(
+    min: usize,
+    max: usize,
+    parser: P,
+    separator: S,
+) -> SepByMN<P, S, T, U>
+where
+    I: Input,
+    P: Parser<I, T>,
+    S: Parser<I, U>,
+{
+    SepByMN {
+        min,
+        max,
+        parser,
+        separator,
+        _phantom: PhantomData,
+    }
+}
+
+/// Parse exactly `n` items separated by a delimiter, e.g. the four octets of an IPv4 address
+/// Composed using sep_by_m_n, so a trailing separator or extra items are left unconsumed
+pub fn count_sep<I, P, S, T, U>(n: usize, parser: P, separator: S) -> SepByMN<P, S, T, U>
+where
+    I: Input,
+    P: Parser<I, T>,
+    S: Parser<I, U>,
+{
+    sep_by_m_n(n, n, parser, separator)
+}
+
+pub struct SepByMN<P, S, T, U> {
+    min: usize,
+    max: usize,
+    parser: P,
+    separator: S,
+    _phantom: PhantomData<(T, U)>,
+}
+
+impl<I, P, S, T, U> Parser<I, Vec<T>> for SepByMN<P, S, T, U>
+where
+    I: Input,
+    P: Parser<I, T>,
+    S: Parser<I, U>,
+{
+    fn parse(&self, input: I) -> ParseResult<I, Vec<T>> {
+        let mut items = Vec::new();
+        if self.max == 0 {
+            return Ok((items, input));
+        }
+
+        let mut remaining = match self.parser.parse(input.clone()) {
+            Ok((first, remaining)) => {
+                items.push(first);
+                remaining
+            }
+            Err(_) if self.min == 0 => return Ok((items, input)),
+            Err(err) => return Err(err),
+        };
+
+        while items.len() < self.max {
+            let Ok((_, after_sep)) = self.separator.parse(remaining.clone()) else {
+                break;
+            };
+            match self.parser.parse(after_sep) {
+                Ok((item, after_item)) => {
+                    items.push(item);
+                    remaining = after_item;
+                }
+                Err(_) => break, // Separator without following element - backtrack
+            }
+        }
+
+        if items.len() < self.min {
+            return Err(ParseError::message(
+                format!(
+                    "expected at least {} items, found {}",
+                    self.min,
+                    items.len()
+                ),
+                input,
+            ));
+        }
+        Ok((items, remaining))
+    }
+}
+
+/// Parse one or more items separated by a delimiter, keeping the separators
+/// Returns the first item plus each `(separator, item)` pair that follows it, so
+/// e.g. an expression evaluator knows which operator joined which operands.
+pub fn sep_by_with_sep<I, P, S, T, U>(parser: P, separator: S) -> SepByWithSep<P, S, T, U>
+where
+    I: Input,
+    P: Parser<I, T>,
+    S: Parser<I, U>,
+{
+    SepByWithSep {
+        parser,
+        separator,
+        _phantom: PhantomData,
+    }
+}
+
+pub struct SepByWithSep<P, S, T, U> {
+    parser: P,
+    separator: S,
+    _phantom: PhantomData<(T, U)>,
+}
+
+impl<I, P, S, T, U> Parser<I, (T, Vec<(U, T)>)> for SepByWithSep<P, S, T, U>
+where
+    I: Input,
+    P: Parser<I, T>,
+    S: Parser<I, U>,
+{
+    fn parse(&self, input: I) -> ParseResult<I, (T, Vec<(U, T)>)> {
+        let (first, remaining) = self.parser.parse(input)?;
+        let (rest, remaining) = fold_separated(
+            &self.parser,
+            &self.separator,
+            Vec::new(),
+            |mut acc, sep, item| {
+                acc.push((sep, item));
+                acc
+            },
+            remaining,
+        )?;
+        Ok(((first, rest), remaining))
+    }
+}
+
+/// Folds over zero or more items separated by a delimiter, without allocating
+pub fn fold_sep_by<I, P, S, T, U, A, F>(
+    parser: P,
+    separator: S,
+    init: A,
+    f: F,
+) -> FoldSepBy<P, S, A, F, T, U>
+where
+    I: Input,
+    P: Parser<I, T>,
+    S: Parser<I, U>,
+    F: FnMut(A, T) -> A,
+{
+    FoldSepBy {
+        parser,
+        separator,
+        init,
+        f,
+        _phantom: PhantomData,
+    }
+}
+
+pub struct FoldSepBy<P, S, A, F, T, U> {
+    parser: P,
+    separator: S,
+    init: A,
+    f: F,
+    _phantom: PhantomData<(T, U)>,
+}
+
+impl<I, P, S, T, U, A, F> Parser<I, A> for FoldSepBy<P, S, A, F, T, U>
+where
+    I: Input,
+    P: Parser<I, T>,
+    S: Parser<I, U>,
+    A: Clone,
+    F: FnMut(A, T) -> A + Clone,
+{
+    fn parse(&self, input: I) -> ParseResult<I, A> {
+        let mut f = self.f.clone();
+        match self.parser.parse(input.clone()) {
+            Ok((first, remaining)) => {
+                let acc = f(self.init.clone(), first);
+                fold_separated(
+                    &self.parser,
+                    &self.separator,
+                    acc,
+                    |acc, _, item| f(acc, item),
+                    remaining,
+                )
+            }
+            Err(_) => Ok((self.init.clone(), input)), // Empty list is valid
+        }
+    }
+}
+
+/// Folds over one or more items separated by a delimiter, without allocating
+pub fn fold_sep_by1<I, P, S, T, U, A, F>(
+    parser: P,
+    separator: S,
+    init: A,
+    f: F,
+) -> FoldSepBy1<P, S, A, F, T, U>
+where
+    I: Input,
+    P: Parser<I, T>,
+    S: Parser<I, U>,
+    F: FnMut(A, T) -> A,
+{
+    FoldSepBy1 {
+        parser,
+        separator,
+        init,
+        f,
+        _phantom: PhantomData,
+    }
+}
+
+pub struct FoldSepBy1<P, S, A, F, T, U> {
+    parser: P,
+    separator: S,
+    init: A,
+    f: F,
+    _phantom: PhantomData<(T, U)>,
+}
+
+impl<I, P, S, T, U, A, F> Parser<I, A> for FoldSepBy1<P, S, A, F, T, U>
+where
+    I: Input,
+    P: Parser<I, T>,
+    S: Parser<I, U>,
+    A: Clone,
+    F: FnMut(A, T) -> A + Clone,
+{
+    fn parse(&self, input: I) -> ParseResult<I, A> {
+        let mut f = self.f.clone();
+        let (first, remaining) = self.parser.parse(input)?;
+        let acc = f(self.init.clone(), first);
+        fold_separated(
+            &self.parser,
+            &self.separator,
+            acc,
+            |acc, _, item| f(acc, item),
+            remaining,
+        )
+    }
+}
+
+/// Parses zero or more separated items straight into any `FromIterator` collection
+/// E.g. `key=value` pairs into a `HashMap`, without building an intermediate `Vec`.
+pub fn sep_by_collect<C, I, P, S, T, U>(parser: P, separator: S) -> SepByCollect<P, S, C, T, U>
+where
+    I: Input,
+    P: Parser<I, T>,
+    S: Parser<I, U>,
+    C: FromIterator<T>,
+{
+    SepByCollect {
+        parser,
+        separator,
+        _phantom: PhantomData,
+    }
+}
+
+pub struct SepByCollect<P, S, C, T, U> {
+    parser: P,
+    separator: S,
+    _phantom: PhantomData<(C, T, U)>,
+}
+
+impl<I, P, S, C, T, U> Parser<I, C> for SepByCollect<P, S, C, T, U>
+where
+    I: Input,
+    P: Parser<I, T>,
+    S: Parser<I, U>,
+    C: FromIterator<T>,
+{
+    fn parse(&self, input: I) -> ParseResult<I, C> {
+        let mut remaining = input;
+        let mut first = true;
+        // Items are parsed lazily as the collection pulls them, stopping at the
+        // first failure; a separator without a following item is not consumed
+        let items = std::iter::from_fn(|| {
+            let attempt = if first {
+                first = false;
+                self.parser.parse(remaining.clone())
+            } else {
+                self.separator
+                    .parse(remaining.clone())
+                    .and_then(|(_, after_sep)| self.parser.parse(after_sep))
+            };
+            let (item, after_item) = attempt.ok()?;
+            remaining = after_item;
+            Some(item)
+        });
+        let collection = items.fuse().collect();
+        Ok((collection, remaining))
+    }
+}
+
+/// Parses a bracketed, separated list such as `[a, b, c]`, allowing it to be empty
+///
+/// A bad item reports the item's own error, while a list that is not followed by
+/// the closing delimiter reports it with "missing closing delimiter" as context.
+/// Trailing separators are rejected unless enabled with `.allow_trailing_sep()`.
+pub fn delimited_list<I, O, P, S, C, V, T, U, W>(
+    open: O,
+    item: P,
+    separator: S,
+    close: C,
+) -> DelimitedList<O, P, S, C, (V, T, U, W)>
+where
+    I: Input,
+    O: Parser<I, V>,
+    P: Parser<I, T>,
+    S: Parser<I, U>,
+    C: Parser<I, W>,
+{
+    DelimitedList {
+        open,
+        item,
+        separator,
+        close,
+        allow_trailing_sep: false,
+        _phantom: PhantomData,
+    }
+}
+
+pub struct DelimitedList<O, P, S, C, R> {
+    open: O,
+    item: P,
+    separator: S,
+    close: C,
+    allow_trailing_sep: bool,
+    _phantom: PhantomData<R>,
+}
+
+impl<O, P, S, C, R> DelimitedList<O, P, S, C, R> {
+    /// Accept a separator right before the closing delimiter, e.g. `[a, b,]`
+    pub fn allow_trailing_sep(mut self) -> Self {
+        self.allow_trailing_sep = true;
+        self
+    }
+}
+
+impl<I, O, P, S, C, V, T, U, W> Parser<I, Vec<T>> for DelimitedList<O, P, S, C, (V, T, U, W)>
+where
+    I: Input,
+    O: Parser<I, V>,
+    P: Parser<I, T>,
+    S: Parser<I, U>,
+    C: Parser<I, W>,
+{
+    fn parse(&self, input: I) -> ParseResult<I, Vec<T>> {
+        let (_, mut remaining) = self.open.parse(input)?;
+        let mut items = Vec::new();
+        if let Ok((_, after_close)) = self.close.parse(remaining.clone()) {
+            return Ok((items, after_close));
+        }
+
+        loop {
+            let (item, after_item) = self.item.parse(remaining)?;
+            items.push(item);
+            remaining = after_item;
+
+            match self.separator.parse(remaining.clone()) {
+                Ok((_, after_sep)) => {
+                    if self.allow_trailing_sep
+                        && let Ok((_, after_close)) = self.close.parse(after_sep.clone())
+                    {
+                        return Ok((items, after_close));
+                    }
+                    remaining = after_sep;
+                }
+                Err(_) => {
+                    return match self.close.parse(remaining) {
+                        Ok((_, after_close)) => Ok((items, after_close)),
+                        Err(err) => Err(ParseError::context("missing closing delimiter", err)),
+                    };
+                }
+            }
+        }
+    }
+}
+
+/// Parses end of input
+pub fn eof<I: Input>() -> Eof<I> {
+    Eof {
+        _phantom: PhantomData,
+    }
+}
+
+pub struct Eof<I> {
+    _phantom: PhantomData<I>,
+}
+
+impl<I: Input> Parser<I, ()> for Eof<I> {
+    fn parse(&self, input: I) -> ParseResult<I, ()> {
+        if input.is_empty() {
+            Ok(((), input))
+        } else {
             Err(ParseError::expected(
                 "end of input",
                 Some("more input"),
@@ -275,3 +1574,31 @@ impl<I: Input> Parser<I, ()> for Eof<I> {
         }
     }
 }
+
+/// Applies a parser repeatedly until the input is exhausted, failing if an iteration consumes nothing
+pub fn repeat_until_eof<I: Input, T, P: Parser<I, T>>(parser: P) -> RepeatUntilEof<P> {
+    RepeatUntilEof { parser }
+}
+
+pub struct RepeatUntilEof<P> {
+    parser: P,
+}
+
+impl<I, T, P> Parser<I, Vec<T>> for RepeatUntilEof<P>
+where
+    I: Input,
+    P: Parser<I, T>,
+{
+    fn parse(&self, mut input: I) -> ParseResult<I, Vec<T>> {
+        let mut results = Vec::new();
+        while !input.is_empty() {
+            let (result, remaining) = self.parser.parse(input.clone())?;
+            if !made_progress(&input, &remaining) {
+                return Err(ParseError::message("parser made no progress", input));
+            }
+            results.push(result);
+            input = remaining;
+        }
+        Ok((results, input))
+    }
+}
@@ -0,0 +1,93 @@
+//! Rendering parse errors as annotated source reports.
+//!
+//! Available with the `diagnostics` feature.
+
+use crate::ParseError;
+use ariadne::{Config, IndexType, Label, Report, ReportKind, Source};
+use std::ops::Range;
+
+/// Render a parse error as a report that underlines the offending position in
+/// `source`
+///
+/// `source` must be the full string the parser was run on, so the position of
+/// each error can be recovered from the remaining input it carries. Errors
+/// without input, such as `UnexpectedEof`, point at the end of the source. Every
+/// alternative of a `Many` error gets its own label, and `Context` messages are
+/// prefixed to the labels they wrap. The output contains no color codes. If the
+/// report cannot be rendered, the error's plain `Display` text is returned instead.
+pub fn report(err: &ParseError<&str>, source: &str) -> String {
+    let mut labels = Vec::new();
+    collect_labels(err, source, "", &mut labels);
+    let position = labels
+        .iter()
+        .map(|(span, _)| span.start)
+        .max()
+        .unwrap_or(source.len());
+
+    let mut output = Vec::new();
+    let written = Report::build(ReportKind::Error, position..position)
+        .with_config(
+            Config::default()
+                .with_color(false)
+                .with_index_type(IndexType::Byte),
+        )
+        .with_message("parse error")
+        .with_labels(
+            labels
+                .into_iter()
+                .map(|(span, message)| Label::new(span).with_message(message)),
+        )
+        .finish()
+        .write(Source::from(source), &mut output);
+    match written {
+        Ok(()) => String::from_utf8_lossy(&output).into_owned(),
+        Err(_) => err.to_string(),
+    }
+}
+
+/// Flatten an error into `(span, message)` labels
+fn collect_labels(
+    err: &ParseError<&str>,
+    source: &str,
+    prefix: &str,
+    labels: &mut Vec<(Range<usize>, String)>,
+) {
+    match err {
+        ParseError::UnexpectedEof | ParseError::Incomplete { .. } => {
+            labels.push((source.len()..source.len(), format!("{}{}", prefix, err)));
+        }
+        ParseError::Expected {
+            expected,
+            found,
+            input,
+        } => {
+            let message = match found {
+                Some(found) => format!("{}expected {}, found {}", prefix, expected, found),
+                None => format!("{}expected {}", prefix, expected),
+            };
+            labels.push((span_at(input, source), message));
+        }
+        ParseError::Message { message, input } => {
+            labels.push((span_at(input, source), format!("{}{}", prefix, message)));
+        }
+        ParseError::Many(errors) => {
+            for error in errors {
+                collect_labels(error, source, prefix, labels);
+            }
+        }
+        ParseError::Context { context, error } => {
+            collect_labels(error, source, &format!("{}{}: ", prefix, context), labels);
+        }
+        ParseError::Named { names, error } => {
+            let prefix = format!("{}in {}: ", prefix, names.join(" > "));
+            collect_labels(error, source, &prefix, labels);
+        }
+    }
+}
+
+/// The span of the next character of `remaining` within `source`
+fn span_at(remaining: &str, source: &str) -> Range<usize> {
+    let start = source.len().saturating_sub(remaining.len());
+    let width = remaining.chars().next().map_or(0, char::len_utf8);
+    start..start + width
+}
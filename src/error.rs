@@ -1,15 +1,54 @@
 //! Error types for the parser combinator library.
 
+use crate::Input;
 use std::fmt;
 
 /// Result type used throughout the parser library.
 pub type ParseResult<I, T> = Result<(T, I), ParseError<I>>;
 
+/// Convenience accessors for the `(value, remaining)` pair inside a `ParseResult`
+pub trait ParseResultExt<I, T> {
+    /// Discard the remaining input, keeping just the parsed value
+    fn value(self) -> Result<T, ParseError<I>>;
+
+    /// Transform the parsed value, leaving the remaining input untouched
+    fn map_value<U, F>(self, f: F) -> ParseResult<I, U>
+    where
+        F: FnOnce(T) -> U;
+
+    /// Transform the remaining input, leaving the parsed value untouched
+    fn map_remaining<J, F>(self, f: F) -> Result<(T, J), ParseError<I>>
+    where
+        F: FnOnce(I) -> J;
+}
+
+impl<I, T> ParseResultExt<I, T> for ParseResult<I, T> {
+    fn value(self) -> Result<T, ParseError<I>> {
+        self.map(|(value, _)| value)
+    }
+
+    fn map_value<U, F>(self, f: F) -> ParseResult<I, U>
+    where
+        F: FnOnce(T) -> U,
+    {
+        self.map(|(value, remaining)| (f(value), remaining))
+    }
+
+    fn map_remaining<J, F>(self, f: F) -> Result<(T, J), ParseError<I>>
+    where
+        F: FnOnce(I) -> J,
+    {
+        self.map(|(value, remaining)| (value, f(remaining)))
+    }
+}
+
 /// Error type representing parsing failures.
 #[derive(Debug, Clone, PartialEq)]
 pub enum ParseError<I> {
     /// Unexpected end of input
     UnexpectedEof,
+    /// Ran out of input, but more input may still arrive (streaming parsing)
+    Incomplete { needed: Option<usize> },
     /// Expected something but found something else
     Expected {
         expected: String,
@@ -20,6 +59,17 @@ pub enum ParseError<I> {
     Message { message: String, input: I },
     /// Multiple errors (for choice combinators)
     Many(Vec<ParseError<I>>),
+    /// An error wrapped with domain context, e.g. "while parsing the date field"
+    Context {
+        context: String,
+        error: Box<ParseError<I>>,
+    },
+    /// An error annotated with the chain of named parsers it bubbled up through,
+    /// outermost first
+    Named {
+        names: Vec<String>,
+        error: Box<ParseError<I>>,
+    },
 }
 
 impl<I> ParseError<I> {
@@ -36,6 +86,11 @@ impl<I> ParseError<I> {
         }
     }
 
+    /// Create a new incomplete error, optionally recording how many more items are needed
+    pub fn incomplete(needed: Option<usize>) -> Self {
+        ParseError::Incomplete { needed }
+    }
+
     /// Create a new message error
     pub fn message(message: impl Into<String>, input: I) -> Self {
         ParseError::Message {
@@ -48,6 +103,320 @@ impl<I> ParseError<I> {
     pub fn many(errors: Vec<ParseError<I>>) -> Self {
         ParseError::Many(errors)
     }
+
+    /// Treat running out of input as a definitive failure, converting every
+    /// nested `Incomplete` into `UnexpectedEof`
+    pub fn into_complete(self) -> Self {
+        match self {
+            ParseError::Incomplete { .. } => ParseError::UnexpectedEof,
+            ParseError::Many(errors) => {
+                ParseError::Many(errors.into_iter().map(ParseError::into_complete).collect())
+            }
+            ParseError::Context { context, error } => ParseError::Context {
+                context,
+                error: Box::new(error.into_complete()),
+            },
+            ParseError::Named { names, error } => ParseError::Named {
+                names,
+                error: Box::new(error.into_complete()),
+            },
+            other => other,
+        }
+    }
+
+    /// Convert the input recorded at every position in this error, e.g. to
+    /// re-wrap errors from a parser that ran over an inner input
+    pub fn map_input<J>(self, f: &impl Fn(I) -> J) -> ParseError<J> {
+        match self {
+            ParseError::UnexpectedEof => ParseError::UnexpectedEof,
+            ParseError::Incomplete { needed } => ParseError::Incomplete { needed },
+            ParseError::Expected {
+                expected,
+                found,
+                input,
+            } => ParseError::Expected {
+                expected,
+                found,
+                input: f(input),
+            },
+            ParseError::Message { message, input } => ParseError::Message {
+                message,
+                input: f(input),
+            },
+            ParseError::Many(errors) => {
+                ParseError::Many(errors.into_iter().map(|error| error.map_input(f)).collect())
+            }
+            ParseError::Context { context, error } => ParseError::Context {
+                context,
+                error: Box::new(error.map_input(f)),
+            },
+            ParseError::Named { names, error } => ParseError::Named {
+                names,
+                error: Box::new(error.map_input(f)),
+            },
+        }
+    }
+
+    /// Wrap an error with context
+    pub fn context(context: impl Into<String>, error: ParseError<I>) -> Self {
+        ParseError::Context {
+            context: context.into(),
+            error: Box::new(error),
+        }
+    }
+
+    /// Whether running out of input caused this error, in any alternative
+    pub fn is_eof(&self) -> bool {
+        self.any_leaf(&|error| matches!(error, ParseError::UnexpectedEof))
+    }
+
+    /// Whether this error, in any alternative, could go away with more input
+    /// A streaming caller can retry such an error once more input has arrived.
+    pub fn is_incomplete(&self) -> bool {
+        self.any_leaf(&|error| matches!(error, ParseError::Incomplete { .. }))
+    }
+
+    /// Whether `predicate` holds for any error at the leaves of this one
+    fn any_leaf(&self, predicate: &impl Fn(&ParseError<I>) -> bool) -> bool {
+        match self {
+            ParseError::Many(errors) => errors.iter().any(|error| error.any_leaf(predicate)),
+            ParseError::Context { error, .. } | ParseError::Named { error, .. } => {
+                error.any_leaf(predicate)
+            }
+            leaf => predicate(leaf),
+        }
+    }
+
+    /// The message of every leaf error, flattening `Many` alternatives in order
+    ///
+    /// Messages leave out the input, and are prefixed with any `Context` and
+    /// `Named` wrappers around them, e.g. `in number: expected digit, found 'x'`.
+    pub fn messages(&self) -> Vec<String> {
+        let mut messages = Vec::new();
+        self.collect_messages("", &mut messages);
+        messages
+    }
+
+    fn collect_messages(&self, prefix: &str, messages: &mut Vec<String>) {
+        match self {
+            ParseError::UnexpectedEof => {
+                messages.push(format!("{}unexpected end of input", prefix));
+            }
+            ParseError::Incomplete { needed } => {
+                let message = match needed {
+                    Some(needed) => format!("{}incomplete input, needed {} more", prefix, needed),
+                    None => format!("{}incomplete input", prefix),
+                };
+                messages.push(message);
+            }
+            ParseError::Expected {
+                expected, found, ..
+            } => {
+                let message = match found {
+                    Some(found) => format!("{}expected {}, found {}", prefix, expected, found),
+                    None => format!("{}expected {}", prefix, expected),
+                };
+                messages.push(message);
+            }
+            ParseError::Message { message, .. } => {
+                messages.push(format!("{}{}", prefix, message));
+            }
+            ParseError::Many(errors) => {
+                for error in errors {
+                    error.collect_messages(prefix, messages);
+                }
+            }
+            ParseError::Context { context, error } => {
+                error.collect_messages(&format!("{}{}: ", prefix, context), messages);
+            }
+            ParseError::Named { names, error } => {
+                let prefix = format!("{}in {}: ", prefix, names.join(" > "));
+                error.collect_messages(&prefix, messages);
+            }
+        }
+    }
+
+    /// Record that an error bubbled up through a parser with the given name
+    /// Names accumulate into a single `Named` error rather than nesting.
+    pub fn named(name: impl Into<String>, error: ParseError<I>) -> Self {
+        match error {
+            ParseError::Named { mut names, error } => {
+                names.insert(0, name.into());
+                ParseError::Named { names, error }
+            }
+            error => ParseError::Named {
+                names: vec![name.into()],
+                error: Box::new(error),
+            },
+        }
+    }
+}
+
+impl<I: Input> ParseError<I> {
+    /// The alternative that got furthest into the input, following nested `Many`
+    /// errors down to a single error
+    ///
+    /// `Context` and `Named` errors are returned whole so their messages are kept.
+    /// Ties go to the earliest alternative.
+    pub fn furthest(&self) -> &ParseError<I> {
+        match self {
+            ParseError::Many(errors) => errors
+                .iter()
+                .map(ParseError::furthest)
+                .min_by_key(|error| error.remaining_len())
+                .unwrap_or(self),
+            other => other,
+        }
+    }
+
+    /// The input at the furthest error, or None if it ran out of input
+    /// See `furthest` for how the error is chosen.
+    pub fn furthest_input(&self) -> Option<&I> {
+        let mut error = self.furthest();
+        loop {
+            match error {
+                ParseError::Expected { input, .. } | ParseError::Message { input, .. } => {
+                    return Some(input);
+                }
+                ParseError::Context { error: inner, .. }
+                | ParseError::Named { error: inner, .. } => {
+                    error = inner.furthest();
+                }
+                _ => return None,
+            }
+        }
+    }
+
+    /// Length of the input left when the error occurred, or `usize::MAX` if unknown
+    fn remaining_len(&self) -> usize {
+        match self {
+            ParseError::UnexpectedEof | ParseError::Incomplete { .. } => 0,
+            ParseError::Expected { input, .. } | ParseError::Message { input, .. } => {
+                input.len().unwrap_or(usize::MAX)
+            }
+            ParseError::Many(errors) => errors
+                .iter()
+                .map(ParseError::remaining_len)
+                .min()
+                .unwrap_or(usize::MAX),
+            ParseError::Context { error, .. } | ParseError::Named { error, .. } => {
+                error.remaining_len()
+            }
+        }
+    }
+}
+
+/// Describe the furthest error in `err` with the line of `source` it occurred on
+///
+/// `source` must be the full string the parser was run on. The report gives the
+/// 1-based line and column, then the line itself with a caret under the position.
+/// If the error's input is not the end of `source`, only the error is shown.
+///
+/// ```text
+/// error at line 2, column 5: expected digit, found 'x' at "x]"
+///   2 | [1, x]
+///     |     ^
+/// ```
+pub fn furthest_error_report(err: &ParseError<&str>, source: &str) -> String {
+    let error = err.furthest();
+    let remaining = err.furthest_input().copied().unwrap_or("");
+    // Errors from a sub-input, such as a `length_value` frame, are not a suffix
+    // of `source`, so there is no line to show
+    let Some((before, after)) = source
+        .len()
+        .checked_sub(remaining.len())
+        .and_then(|offset| source.split_at_checked(offset))
+        .filter(|(_, after)| *after == remaining)
+    else {
+        return format!("error: {}", error);
+    };
+    let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+    let line_end = after.find('\n').unwrap_or(after.len());
+    let line_number = before.matches('\n').count() + 1;
+    let column = before[line_start..].chars().count() + 1;
+    let gutter = line_number.to_string();
+    format!(
+        "error at line {}, column {}: {}\n  {} | {}{}\n  {} | {}^",
+        line_number,
+        column,
+        error,
+        gutter,
+        &before[line_start..],
+        &after[..line_end],
+        " ".repeat(gutter.len()),
+        " ".repeat(column - 1),
+    )
+}
+
+/// Maximum number of characters of the remaining input shown by `Display`
+const DISPLAY_INPUT_CHARS: usize = 20;
+
+/// Debug-format an input for display, cut to a short window with an ellipsis
+///
+/// Formatting stops as soon as the window is full, so showing an error costs the
+/// same however much input remains. An escape sequence such as `\n` or `\u{301}`
+/// counts as one character and is never cut, and an opening quote does not count.
+/// The error itself keeps the full input.
+fn write_truncated<I: fmt::Debug>(f: &mut fmt::Formatter<'_>, input: &I) -> fmt::Result {
+    let mut window = Window {
+        f,
+        shown: 0,
+        escape: Escape::None,
+        started: false,
+        full: false,
+    };
+    match fmt::write(&mut window, format_args!("{:?}", input)) {
+        Err(_) if window.full => window.f.write_str("..."),
+        result => result,
+    }
+}
+
+/// Where a `Window` is within an escape sequence of the formatted input
+#[derive(PartialEq)]
+enum Escape {
+    None,
+    /// Just after a backslash
+    Start,
+    /// Inside the braces of a `\u{...}` escape
+    Unicode,
+}
+
+/// A writer that passes at most `DISPLAY_INPUT_CHARS` characters through to `f`,
+/// then fails with `full` set
+struct Window<'a, 'b> {
+    f: &'a mut fmt::Formatter<'b>,
+    shown: usize,
+    escape: Escape,
+    started: bool,
+    full: bool,
+}
+
+impl fmt::Write for Window<'_, '_> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        s.chars().try_for_each(|c| self.write_char(c))
+    }
+
+    fn write_char(&mut self, c: char) -> fmt::Result {
+        if self.escape == Escape::None && self.shown == DISPLAY_INPUT_CHARS {
+            self.full = true;
+            return Err(fmt::Error);
+        }
+        self.f.write_char(c)?;
+        let opening_quote = !self.started && c == '"';
+        self.started = true;
+        self.escape = match self.escape {
+            Escape::None if c == '\\' => Escape::Start,
+            Escape::Start if c == 'u' => Escape::Unicode,
+            Escape::Unicode if c != '}' => Escape::Unicode,
+            _ => {
+                if !opening_quote {
+                    self.shown += 1;
+                }
+                Escape::None
+            }
+        };
+        Ok(())
+    }
 }
 
 impl<I> fmt::Display for ParseError<I>
@@ -57,6 +426,13 @@ where
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             ParseError::UnexpectedEof => write!(f, "unexpected end of input"),
+            ParseError::Incomplete { needed } => {
+                write!(f, "incomplete input")?;
+                if let Some(needed) = needed {
+                    write!(f, ", needed {} more", needed)?;
+                }
+                Ok(())
+            }
             ParseError::Expected {
                 expected,
                 found,
@@ -66,10 +442,12 @@ where
                 if let Some(found) = found {
                     write!(f, ", found {}", found)?;
                 }
-                write!(f, " at {:?}", input)
+                write!(f, " at ")?;
+                write_truncated(f, input)
             }
             ParseError::Message { message, input } => {
-                write!(f, "{} at {:?}", message, input)
+                write!(f, "{} at ", message)?;
+                write_truncated(f, input)
             }
             ParseError::Many(errors) => {
                 write!(f, "multiple errors: ")?;
@@ -81,8 +459,42 @@ where
                 }
                 Ok(())
             }
+            ParseError::Context { context, error } => write!(f, "{}: {}", context, error),
+            ParseError::Named { names, error } => {
+                write!(f, "in {}: {}", names.join(" > "), error)
+            }
+        }
+    }
+}
+
+impl<I> std::error::Error for ParseError<I>
+where
+    I: fmt::Debug + Send + Sync + 'static,
+{
+    /// The first alternative of a `Many` error, or the error wrapped by `Context`
+    /// or `Named`
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ParseError::Many(errors) => errors
+                .first()
+                .map(|error| error as &(dyn std::error::Error + 'static)),
+            ParseError::Context { error, .. } | ParseError::Named { error, .. } => {
+                Some(error.as_ref())
+            }
+            _ => None,
         }
     }
 }
 
-impl<I> std::error::Error for ParseError<I> where I: fmt::Debug + Send + Sync + 'static {}
+/// Convert into an `io::Error` of kind `InvalidData`, carrying the displayed message
+///
+/// The message is rendered eagerly, so borrowed inputs such as `&str` convert
+/// without needing to outlive the resulting error.
+impl<I> From<ParseError<I>> for std::io::Error
+where
+    I: fmt::Debug,
+{
+    fn from(error: ParseError<I>) -> Self {
+        std::io::Error::new(std::io::ErrorKind::InvalidData, error.to_string())
+    }
+}
@@ -0,0 +1,4 @@
+//! Reference parsers for common data formats, built from the public combinators.
+
+#[cfg(feature = "json")]
+pub mod json;
@@ -0,0 +1,206 @@
+//! A complete JSON parser, written with the library's own combinators.
+//!
+//! Available with the `json` feature. Besides being usable as is, it serves as a
+//! worked example of a recursive grammar: `Value` implements `Parser` by hand so
+//! arrays and objects can refer back to it.
+
+use crate::{
+    DepthLimit, Number, ParseError, ParseResult, Parser, boolean, char, delimited_list,
+    json_number, lexeme, nested, null, one_of, parse_complete, preceded, spaces, string, take,
+    take_while1,
+};
+use std::fmt;
+
+/// A parsed JSON document
+///
+/// Object members keep their source order, and duplicate keys are preserved.
+#[derive(Debug, Clone, PartialEq)]
+pub enum JsonValue {
+    Null,
+    Bool(bool),
+    Number(Number),
+    String(String),
+    Array(Vec<JsonValue>),
+    Object(Vec<(String, JsonValue)>),
+}
+
+/// Parse a whole JSON document, allowing surrounding whitespace
+pub fn parse(text: &str) -> Result<JsonValue, ParseError<&str>> {
+    parse_complete(&preceded(spaces(), value()), text)
+}
+
+/// How deeply `value` lets arrays and objects nest by default
+pub const DEFAULT_MAX_DEPTH: usize = 128;
+
+/// Parse a single JSON value, skipping any whitespace that follows it
+///
+/// Arrays and objects may nest at most `DEFAULT_MAX_DEPTH` levels deep, so untrusted
+/// input cannot overflow the stack; deeper input fails with "maximum nesting depth
+/// exceeded".
+pub fn value() -> Value {
+    Value {
+        limit: DepthLimit::new(DEFAULT_MAX_DEPTH),
+    }
+}
+
+pub struct Value {
+    limit: DepthLimit,
+}
+
+impl Value {
+    /// Allow arrays and objects to nest at most `max` levels deep
+    pub fn max_depth(self, max: usize) -> Self {
+        Value {
+            limit: DepthLimit::new(max),
+        }
+    }
+}
+
+impl<'a> Parser<&'a str, JsonValue> for Value {
+    fn parse(&self, input: &'a str) -> ParseResult<&'a str, JsonValue> {
+        // The first character decides the kind of value, so a failure reports
+        // what went wrong inside that value instead of every alternative
+        let (value, remaining) = match input.chars().next() {
+            Some('n') => null().map(|_| JsonValue::Null).parse(input),
+            Some('t' | 'f') => boolean().map(JsonValue::Bool).parse(input),
+            Some('-' | '0'..='9') => json_number().map(JsonValue::Number).parse(input),
+            Some('"') => StringLiteral.map(JsonValue::String).parse(input),
+            Some('[') => {
+                let array = delimited_list(lexeme(char('[')), self, lexeme(char(',')), char(']'));
+                nested(array, &self.limit)
+                    .map(JsonValue::Array)
+                    .parse(input)
+            }
+            Some('{') => {
+                let member = lexeme(StringLiteral).skip(lexeme(char(':'))).and(self);
+                let object =
+                    delimited_list(lexeme(char('{')), member, lexeme(char(',')), char('}'));
+                nested(object, &self.limit)
+                    .map(JsonValue::Object)
+                    .parse(input)
+            }
+            Some(c) => Err(ParseError::expected(
+                "JSON value",
+                Some(format!("character '{}'", c)),
+                input,
+            )),
+            None => Err(ParseError::incomplete(Some(1))),
+        }?;
+        spaces().parse(remaining).map(|(_, rest)| (value, rest))
+    }
+}
+
+/// Parses a double-quoted JSON string, decoding its escapes
+struct StringLiteral;
+
+impl<'a> Parser<&'a str, String> for StringLiteral {
+    fn parse(&self, input: &'a str) -> ParseResult<&'a str, String> {
+        let unescaped =
+            take_while1(|c: &char| *c != '"' && *c != '\\' && *c >= ' ').map(str::to_string);
+        let escaped = preceded(char('\\'), one_of("\"\\/bfnrt")).map(|c| {
+            match c {
+                'b' => '\u{8}',
+                'f' => '\u{c}',
+                'n' => '\n',
+                'r' => '\r',
+                't' => '\t',
+                c => c,
+            }
+            .to_string()
+        });
+        // Consecutive `\u` escapes are decoded together, so surrogate pairs combine
+        let unicode = preceded(string("\\u"), hex_code_unit())
+            .many1()
+            .verify_map(|units| {
+                char::decode_utf16(units)
+                    .collect::<Result<String, _>>()
+                    .map_err(|e| format!("unpaired surrogate {:#06x}", e.unpaired_surrogate()))
+            });
+
+        let (_, mut remaining) = char('"').parse(input)?;
+        let mut text = String::new();
+        loop {
+            // The next character decides the kind of fragment, so a bad escape is
+            // reported as such instead of as a missing closing quote
+            let (fragment, rest) = match remaining.chars().next() {
+                Some('"') => return Ok((text, &remaining[1..])),
+                Some('\\') if remaining.starts_with("\\u") => unicode.parse(remaining)?,
+                Some('\\') => escaped.parse(remaining)?,
+                Some(c) if c < ' ' => {
+                    return Err(ParseError::expected(
+                        "'\"'",
+                        Some(format!("{:?}", c)),
+                        remaining,
+                    ));
+                }
+                Some(_) => unescaped.parse(remaining)?,
+                None => return Err(ParseError::incomplete(Some(1))),
+            };
+            text.push_str(&fragment);
+            remaining = rest;
+        }
+    }
+}
+
+/// Parse the four hex digits of a `\u` escape
+fn hex_code_unit<'a>() -> impl Parser<&'a str, u16> {
+    take(4).verify_map(|digits: &str| {
+        if digits.chars().all(|c| c.is_ascii_hexdigit()) {
+            u16::from_str_radix(digits, 16).map_err(|e| e.to_string())
+        } else {
+            Err(format!("invalid unicode escape '{}'", digits))
+        }
+    })
+}
+
+/// Serializes as compact JSON, which `parse` reads back into an equal value
+impl fmt::Display for JsonValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            JsonValue::Null => write!(f, "null"),
+            JsonValue::Bool(b) => write!(f, "{}", b),
+            JsonValue::Number(Number::Int(n)) => write!(f, "{}", n),
+            // Debug formatting always keeps a `.` or exponent, so the value stays a float
+            JsonValue::Number(Number::Float(n)) => write!(f, "{:?}", n),
+            JsonValue::String(s) => write_string(f, s),
+            JsonValue::Array(items) => {
+                write!(f, "[")?;
+                for (index, item) in items.iter().enumerate() {
+                    if index > 0 {
+                        write!(f, ",")?;
+                    }
+                    write!(f, "{}", item)?;
+                }
+                write!(f, "]")
+            }
+            JsonValue::Object(members) => {
+                write!(f, "{{")?;
+                for (index, (key, item)) in members.iter().enumerate() {
+                    if index > 0 {
+                        write!(f, ",")?;
+                    }
+                    write_string(f, key)?;
+                    write!(f, ":{}", item)?;
+                }
+                write!(f, "}}")
+            }
+        }
+    }
+}
+
+/// Write a string as a quoted JSON literal, escaping where required
+fn write_string(f: &mut fmt::Formatter<'_>, s: &str) -> fmt::Result {
+    write!(f, "\"")?;
+    for c in s.chars() {
+        match c {
+            '"' => write!(f, "\\\"")?,
+            '\\' => write!(f, "\\\\")?,
+            '\n' => write!(f, "\\n")?,
+            '\r' => write!(f, "\\r")?,
+            '\t' => write!(f, "\\t")?,
+            c if c < ' ' => write!(f, "\\u{:04x}", c as u32)?,
+            c => write!(f, "{}", c)?,
+        }
+    }
+    write!(f, "\"")
+}
@@ -0,0 +1,127 @@
+//! Indentation-sensitive parsing over `StrInput`.
+//!
+//! Layout rules are expressed with the line and column that `StrInput` tracks:
+//! `block` collects items aligned on one column, `indented` requires a parser to
+//! start on a later line, further right than the current line, and `same_line`
+//! forbids a line break before a parser.
+
+use crate::{Input, ParseError, ParseResult, Parser, StrInput};
+
+/// Skip any whitespace, including line breaks
+fn skip_whitespace(mut input: StrInput<'_>) -> StrInput<'_> {
+    while let Some((c, remaining)) = input.uncons()
+        && c.is_whitespace()
+    {
+        input = remaining;
+    }
+    input
+}
+
+/// The line of the last non-whitespace character consumed between `start` and
+/// `end`, or the line of `start` if there is none
+fn last_content_line(start: &StrInput<'_>, end: &StrInput<'_>) -> usize {
+    let consumed = end
+        .consumed_since(start)
+        .map_or("", |prefix| prefix.remaining());
+    start.line() + consumed.trim_end().matches('\n').count()
+}
+
+/// Parse one or more items that each start a line at the column of the first
+///
+/// The block ends at the first following line that is indented less, or at the
+/// end of input. A line indented more, which no item consumed, is an error, as
+/// is a line at the block's column that does not parse as an item.
+pub fn block<P>(item: P) -> Block<P> {
+    Block { item }
+}
+
+pub struct Block<P> {
+    item: P,
+}
+
+impl<'a, T, P> Parser<StrInput<'a>, Vec<T>> for Block<P>
+where
+    P: Parser<StrInput<'a>, T>,
+{
+    fn parse(&self, input: StrInput<'a>) -> ParseResult<StrInput<'a>, Vec<T>> {
+        let column = input.column();
+        let (first, mut remaining) = self.item.parse(input.clone())?;
+        let mut items = vec![first];
+        let mut item_start = input;
+        loop {
+            let next = skip_whitespace(remaining.clone());
+            let end_line = last_content_line(&item_start, &remaining);
+            if next.is_empty() || next.line() == end_line || next.column() < column {
+                return Ok((items, remaining));
+            }
+            if next.column() > column {
+                return Err(ParseError::message("unexpected indentation", next));
+            }
+            let (item, after_item) = self.item.parse(next.clone())?;
+            items.push(item);
+            item_start = next;
+            remaining = after_item;
+        }
+    }
+}
+
+/// Parse `parser` on a later line, indented further than the current line
+///
+/// E.g. after the `:` of `if x:`, the body must start on a new line to the right
+/// of the `if`. Leading whitespace and line breaks are skipped.
+pub fn indented<P>(parser: P) -> Indented<P> {
+    Indented { parser }
+}
+
+pub struct Indented<P> {
+    parser: P,
+}
+
+impl<'a, T, P> Parser<StrInput<'a>, T> for Indented<P>
+where
+    P: Parser<StrInput<'a>, T>,
+{
+    fn parse(&self, input: StrInput<'a>) -> ParseResult<StrInput<'a>, T> {
+        let indent = input.line_indent();
+        let next = skip_whitespace(input.clone());
+        if next.line() == input.line() {
+            return Err(ParseError::message(
+                "expected an indented block on a new line",
+                next,
+            ));
+        }
+        if next.column() <= indent {
+            return Err(ParseError::message(
+                format!("expected indentation beyond column {}", indent),
+                next,
+            ));
+        }
+        self.parser.parse(next)
+    }
+}
+
+/// Parse `parser` after any spaces or tabs, failing if it would start on a new line
+pub fn same_line<P>(parser: P) -> SameLine<P> {
+    SameLine { parser }
+}
+
+pub struct SameLine<P> {
+    parser: P,
+}
+
+impl<'a, T, P> Parser<StrInput<'a>, T> for SameLine<P>
+where
+    P: Parser<StrInput<'a>, T>,
+{
+    fn parse(&self, mut input: StrInput<'a>) -> ParseResult<StrInput<'a>, T> {
+        while let Some((c, remaining)) = input.uncons()
+            && (c == ' ' || c == '\t')
+        {
+            input = remaining;
+        }
+        if matches!(input.uncons(), Some(('\n' | '\r', _))) {
+            return Err(ParseError::message("expected more on the same line", input));
+        }
+        self.parser.parse(input)
+    }
+}
@@ -1,11 +1,15 @@
 //! Input stream trait for general input types.
 
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::rc::Rc;
+
 /// Trait for types that can be used as input to parsers.
 ///
 /// This allows parsing over any type that can provide iterator-like access,
 /// not just strings or byte slices. Examples include HTML DOM trees, JSON values,
-/// or custom data structures.
-pub trait Input: Clone + PartialEq {
+/// or custom data structures. Inputs need not be `PartialEq`.
+pub trait Input: Clone {
     /// The type of individual items in the input stream
     type Item: Clone + PartialEq + std::fmt::Debug;
 
@@ -13,6 +17,28 @@ pub trait Input: Clone + PartialEq {
     /// Returns None if the input is empty.
     fn uncons(&self) -> Option<(Self::Item, Self)>;
 
+    /// Splits off the first `n` items, returning `(consumed_prefix, remaining)`.
+    /// Returns None if fewer than `n` items remain.
+    ///
+    /// The default implementation returns None, since a prefix can only be built
+    /// by input types that know how to represent a sub-stream of themselves. Every
+    /// built-in input overrides it; a custom input that does not cannot be used with
+    /// `take` or the parsers built on it, such as `length_data` and `length_value`.
+    fn uncons_n(&self, n: usize) -> Option<(Self, Self)> {
+        let _ = n;
+        None
+    }
+
+    /// The prefix of `earlier` that was consumed to reach `self`, a later state of
+    /// the same input, computed without walking the consumed items.
+    ///
+    /// The default implementation returns None; `consumed` then falls back to
+    /// walking from `earlier` with `uncons`.
+    fn consumed_since(&self, earlier: &Self) -> Option<Self> {
+        let _ = earlier;
+        None
+    }
+
     /// Returns true if the input stream is empty
     fn is_empty(&self) -> bool {
         self.uncons().is_none()
@@ -22,10 +48,89 @@ pub trait Input: Clone + PartialEq {
     fn len(&self) -> Option<usize> {
         None
     }
+
+    /// Whether `self` is at the same point as `other`, a state of the same input.
+    /// Repetitions use this to stop when a parser succeeds without consuming anything.
+    ///
+    /// There is no default: a wrong answer either loops forever or stops too early,
+    /// so every input type must say how its positions compare, e.g. by `len`.
+    fn same_position(&self, other: &Self) -> bool;
+
+    /// A key identifying this state of the input, e.g. a slice's address and length.
+    /// Two states share a key only when they are at the same point of the same
+    /// underlying input and end at the same place.
+    ///
+    /// `memoize` caches results under this key. The default returns None, which
+    /// opts the input out of caching.
+    fn position_key(&self) -> Option<(usize, usize, usize)> {
+        None
+    }
+}
+
+/// The part of `before` that was consumed to reach `after`, a later state of the
+/// same input.
+///
+/// Uses `Input::consumed_since` when the input supports it, and otherwise walks
+/// forward from `before` one item at a time, so it costs O(consumed).
+/// Returns None if `after` is not reachable from `before`, or if the input type
+/// cannot represent a prefix (see `uncons_n`).
+pub(crate) fn consumed<I: Input>(before: &I, after: &I) -> Option<I> {
+    if let Some(prefix) = after.consumed_since(before) {
+        return Some(prefix);
+    }
+    let mut current = before.clone();
+    let mut count = 0;
+    loop {
+        let reached = match (current.len(), after.len()) {
+            (Some(current_len), Some(after_len)) => current_len <= after_len,
+            _ => current.same_position(after),
+        };
+        if reached {
+            break;
+        }
+        current = current.uncons()?.1;
+        count += 1;
+    }
+    before.uncons_n(count).map(|(prefix, _)| prefix)
+}
+
+/// Whether `after` is strictly shorter than `before`.
+/// Compares lengths when the input reports them, and falls back to `same_position` otherwise.
+pub(crate) fn made_progress<I: Input>(before: &I, after: &I) -> bool {
+    match (before.len(), after.len()) {
+        (Some(before_len), Some(after_len)) => after_len < before_len,
+        _ => !before.same_position(after),
+    }
+}
+
+/// Enough of an input's state to tell later whether parsing advanced past it
+///
+/// Inputs that report a `len` are marked by it, so the input itself need not be
+/// copied; others keep a clone to compare with `same_position`.
+pub(crate) enum Mark<I> {
+    Len(usize),
+    Input(I),
+}
+
+impl<I: Input> Mark<I> {
+    pub(crate) fn new(input: &I) -> Self {
+        match input.len() {
+            Some(len) => Mark::Len(len),
+            None => Mark::Input(input.clone()),
+        }
+    }
+
+    /// Whether `after` is strictly further along than the marked input
+    pub(crate) fn progressed_to(&self, after: &I) -> bool {
+        match self {
+            Mark::Len(len) => after.len().is_none_or(|after_len| after_len < *len),
+            Mark::Input(before) => made_progress(before, after),
+        }
+    }
 }
 
 /// Implementation for string slices - the most common case
-impl<'a> Input for &'a str {
+impl Input for &str {
     type Item = char;
 
     fn uncons(&self) -> Option<(Self::Item, Self)> {
@@ -33,25 +138,64 @@ impl<'a> Input for &'a str {
         chars.next().map(|c| (c, &self[c.len_utf8()..]))
     }
 
+    fn uncons_n(&self, n: usize) -> Option<(Self, Self)> {
+        let end = self
+            .char_indices()
+            .map(|(i, _)| i)
+            .chain(std::iter::once(str::len(self)))
+            .nth(n)?;
+        Some(self.split_at(end))
+    }
+
+    fn consumed_since(&self, earlier: &Self) -> Option<Self> {
+        let consumed = (self.as_ptr() as usize).checked_sub(earlier.as_ptr() as usize)?;
+        let is_suffix = consumed + str::len(self) == str::len(earlier);
+        is_suffix.then(|| earlier.get(..consumed)).flatten()
+    }
+
     fn len(&self) -> Option<usize> {
         Some(str::len(self))
     }
+
+    fn same_position(&self, other: &Self) -> bool {
+        str::len(self) == str::len(other)
+    }
+
+    fn position_key(&self) -> Option<(usize, usize, usize)> {
+        Some((self.as_ptr() as usize, 0, str::len(self)))
+    }
 }
 
-/// Implementation for byte slices
-impl<'a> Input for &'a [u8] {
-    type Item = u8;
+/// Implementation for slices, including byte slices and token streams
+/// Zero-copy: `uncons` clones only the item, never the rest of the slice.
+impl<T: Clone + PartialEq + std::fmt::Debug> Input for &[T] {
+    type Item = T;
 
     fn uncons(&self) -> Option<(Self::Item, Self)> {
-        if self.is_empty() {
-            None
-        } else {
-            Some((self[0], &self[1..]))
-        }
+        self.split_first()
+            .map(|(item, remaining)| (item.clone(), remaining))
+    }
+
+    fn uncons_n(&self, n: usize) -> Option<(Self, Self)> {
+        self.split_at_checked(n)
+    }
+
+    fn consumed_since(&self, earlier: &Self) -> Option<Self> {
+        let (start, end) = (self.as_ptr_range(), earlier.as_ptr_range());
+        let is_suffix = start.end == end.end && end.start <= start.start;
+        is_suffix.then(|| &earlier[..<[T]>::len(earlier) - <[T]>::len(self)])
     }
 
     fn len(&self) -> Option<usize> {
-        Some(<[u8]>::len(self))
+        Some(<[T]>::len(self))
+    }
+
+    fn same_position(&self, other: &Self) -> bool {
+        <[T]>::len(self) == <[T]>::len(other)
+    }
+
+    fn position_key(&self) -> Option<(usize, usize, usize)> {
+        Some((self.as_ptr() as usize, 0, <[T]>::len(self)))
     }
 }
 
@@ -67,7 +211,558 @@ impl<T: Clone + PartialEq + std::fmt::Debug> Input for Vec<T> {
         }
     }
 
+    fn uncons_n(&self, n: usize) -> Option<(Self, Self)> {
+        self.split_at_checked(n)
+            .map(|(prefix, rest)| (prefix.to_vec(), rest.to_vec()))
+    }
+
     fn len(&self) -> Option<usize> {
         Some(Vec::len(self))
     }
+
+    fn same_position(&self, other: &Self) -> bool {
+        Vec::len(self) == Vec::len(other)
+    }
+}
+
+/// Trait for inputs that know their absolute offset within the original stream.
+///
+/// Plain slices cannot know where they start relative to the original input, so
+/// position tracking is opted into by wrapping an input in `Located`.
+pub trait Positioned: Input {
+    /// Offset of the next item from the start of the original input
+    fn position(&self) -> usize;
+}
+
+/// A range of input offsets, from `start` (inclusive) to `end` (exclusive)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+}
+
+impl Span {
+    /// Create a new span
+    pub fn new(start: usize, end: usize) -> Self {
+        Span { start, end }
+    }
+
+    /// Number of offsets covered by the span
+    pub fn len(&self) -> usize {
+        self.end - self.start
+    }
+
+    /// Returns true if the span covers nothing
+    pub fn is_empty(&self) -> bool {
+        self.start == self.end
+    }
+}
+
+/// Wraps an input, tracking its offset from the start of the original input.
+///
+/// Offsets are measured in the units of the inner input's `len`, so a
+/// `Located<&str>` reports byte offsets. Inputs without a known length advance
+/// by one per item.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Located<I> {
+    input: I,
+    offset: usize,
+}
+
+impl<I: Input> Located<I> {
+    /// Start tracking positions from offset zero
+    pub fn new(input: I) -> Self {
+        Located { input, offset: 0 }
+    }
+
+    /// The underlying input
+    pub fn input(&self) -> &I {
+        &self.input
+    }
+
+    /// Unwrap into the underlying input
+    pub fn into_inner(self) -> I {
+        self.input
+    }
+
+    /// Advance to a later state of the same input
+    fn advance(&self, remaining: I, items: usize) -> Self {
+        let distance = match (self.input.len(), remaining.len()) {
+            (Some(before), Some(after)) => before - after,
+            _ => items,
+        };
+        Located {
+            input: remaining,
+            offset: self.offset + distance,
+        }
+    }
+}
+
+impl<I: Input> Input for Located<I> {
+    type Item = I::Item;
+
+    fn uncons(&self) -> Option<(Self::Item, Self)> {
+        self.input
+            .uncons()
+            .map(|(item, remaining)| (item, self.advance(remaining, 1)))
+    }
+
+    fn uncons_n(&self, n: usize) -> Option<(Self, Self)> {
+        self.input.uncons_n(n).map(|(prefix, remaining)| {
+            let prefix = Located {
+                input: prefix,
+                offset: self.offset,
+            };
+            (prefix, self.advance(remaining, n))
+        })
+    }
+
+    fn consumed_since(&self, earlier: &Self) -> Option<Self> {
+        self.input
+            .consumed_since(&earlier.input)
+            .map(|prefix| Located {
+                input: prefix,
+                offset: earlier.offset,
+            })
+    }
+
+    fn len(&self) -> Option<usize> {
+        self.input.len()
+    }
+
+    fn same_position(&self, other: &Self) -> bool {
+        self.input.same_position(&other.input)
+    }
+
+    fn position_key(&self) -> Option<(usize, usize, usize)> {
+        self.input.position_key()
+    }
+}
+
+impl<I: Input> Positioned for Located<I> {
+    fn position(&self) -> usize {
+        self.offset
+    }
+}
+
+/// A cursor over a shared `VecDeque`, for parsing items accumulated incrementally
+/// (e.g. received from a channel) without copying them into a `Vec`.
+///
+/// Clones share the same buffer through an `Rc`, so cloning and `uncons` are O(1).
+/// Positions are indices into the deque.
+#[derive(Debug, Clone)]
+pub struct DequeInput<T> {
+    items: Rc<VecDeque<T>>,
+    start: usize,
+    end: usize,
+}
+
+impl<T> DequeInput<T> {
+    /// Create a cursor at the front of the deque
+    pub fn new(items: VecDeque<T>) -> Self {
+        Self::from_rc(Rc::new(items))
+    }
+
+    /// Create a cursor at the front of an already shared deque
+    pub fn from_rc(items: Rc<VecDeque<T>>) -> Self {
+        let end = items.len();
+        DequeInput {
+            items,
+            start: 0,
+            end,
+        }
+    }
+
+    /// Iterate over the remaining items
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.items.range(self.start..self.end)
+    }
+}
+
+impl<T> From<VecDeque<T>> for DequeInput<T> {
+    fn from(items: VecDeque<T>) -> Self {
+        DequeInput::new(items)
+    }
+}
+
+impl<T: PartialEq> PartialEq for DequeInput<T> {
+    fn eq(&self, other: &Self) -> bool {
+        if Rc::ptr_eq(&self.items, &other.items) {
+            return self.start == other.start && self.end == other.end;
+        }
+        self.iter().eq(other.iter())
+    }
+}
+
+impl<T: Clone + PartialEq + std::fmt::Debug> Input for DequeInput<T> {
+    type Item = T;
+
+    fn uncons(&self) -> Option<(Self::Item, Self)> {
+        if self.start == self.end {
+            return None;
+        }
+        let item = self.items[self.start].clone();
+        let remaining = DequeInput {
+            start: self.start + 1,
+            ..self.clone()
+        };
+        Some((item, remaining))
+    }
+
+    fn uncons_n(&self, n: usize) -> Option<(Self, Self)> {
+        let split = self
+            .start
+            .checked_add(n)
+            .filter(|&split| split <= self.end)?;
+        let prefix = DequeInput {
+            end: split,
+            ..self.clone()
+        };
+        let remaining = DequeInput {
+            start: split,
+            ..self.clone()
+        };
+        Some((prefix, remaining))
+    }
+
+    fn len(&self) -> Option<usize> {
+        Some(self.end - self.start)
+    }
+
+    fn same_position(&self, other: &Self) -> bool {
+        self.start == other.start && self.end == other.end
+    }
+
+    fn position_key(&self) -> Option<(usize, usize, usize)> {
+        Some((Rc::as_ptr(&self.items) as usize, self.start, self.end))
+    }
+}
+
+impl<T: Clone + PartialEq + std::fmt::Debug> Positioned for DequeInput<T> {
+    fn position(&self) -> usize {
+        self.start
+    }
+}
+
+/// An input that lazily pulls items from an iterator, such as a lexer's token
+/// stream, so it can be used with backtracking parsers.
+///
+/// Items are buffered as they are pulled, and the buffer is shared by every
+/// clone, so backtracking re-reads buffered items instead of re-running the
+/// iterator. Each `IterInput` is just a cursor into that buffer. Inputs created
+/// from different iterators never compare equal.
+#[derive(Clone)]
+pub struct IterInput<T> {
+    buffer: Rc<RefCell<IterBuffer<T>>>,
+    index: usize,
+    /// Where a prefix split off by `uncons_n` ends; None reads to the end of the iterator
+    end: Option<usize>,
+}
+
+struct IterBuffer<T> {
+    items: Vec<T>,
+    source: Box<dyn Iterator<Item = T>>,
+}
+
+impl<T> IterInput<T> {
+    /// Create an input positioned before the first item of the iterator
+    pub fn new<It>(iter: It) -> Self
+    where
+        It: IntoIterator<Item = T>,
+        It::IntoIter: 'static,
+    {
+        IterInput {
+            buffer: Rc::new(RefCell::new(IterBuffer {
+                items: Vec::new(),
+                source: Box::new(iter.into_iter()),
+            })),
+            index: 0,
+            end: None,
+        }
+    }
+}
+
+impl<T> std::fmt::Debug for IterInput<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("IterInput")
+            .field("index", &self.index)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<T> PartialEq for IterInput<T> {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.buffer, &other.buffer)
+            && self.index == other.index
+            && self.end == other.end
+    }
+}
+
+impl<T: Clone + PartialEq + std::fmt::Debug> Input for IterInput<T> {
+    type Item = T;
+
+    fn uncons(&self) -> Option<(Self::Item, Self)> {
+        if self.end == Some(self.index) {
+            return None;
+        }
+        let mut buffer = self.buffer.borrow_mut();
+        if self.index == buffer.items.len() {
+            let item = buffer.source.next()?;
+            buffer.items.push(item);
+        }
+        let item = buffer.items[self.index].clone();
+        let remaining = IterInput {
+            buffer: Rc::clone(&self.buffer),
+            index: self.index + 1,
+            end: self.end,
+        };
+        Some((item, remaining))
+    }
+
+    fn uncons_n(&self, n: usize) -> Option<(Self, Self)> {
+        let mut remaining = self.clone();
+        for _ in 0..n {
+            remaining = remaining.uncons()?.1;
+        }
+        let prefix = IterInput {
+            end: Some(remaining.index),
+            ..self.clone()
+        };
+        Some((prefix, remaining))
+    }
+
+    fn consumed_since(&self, earlier: &Self) -> Option<Self> {
+        let same_buffer = Rc::ptr_eq(&self.buffer, &earlier.buffer);
+        (same_buffer && earlier.index <= self.index).then(|| IterInput {
+            end: Some(self.index),
+            ..earlier.clone()
+        })
+    }
+
+    fn same_position(&self, other: &Self) -> bool {
+        self == other
+    }
+
+    fn position_key(&self) -> Option<(usize, usize, usize)> {
+        let end = self.end.unwrap_or(usize::MAX);
+        Some((Rc::as_ptr(&self.buffer) as usize, self.index, end))
+    }
+}
+
+impl<T: Clone + PartialEq + std::fmt::Debug> Positioned for IterInput<T> {
+    fn position(&self) -> usize {
+        self.index
+    }
+}
+
+/// A string input that tracks the line and column of the next character, as
+/// well as its byte offset.
+///
+/// Lines and columns are 1-based. Columns count `char`s by default; with the
+/// `graphemes` feature, `StrInput::with_graphemes` counts grapheme clusters
+/// instead, so combining characters and emoji sequences occupy one column.
+/// Consuming `\n` starts a new line.
+#[derive(Debug, Clone)]
+pub struct StrInput<'a> {
+    source: &'a str,
+    offset: usize,
+    line: usize,
+    column: usize,
+    #[cfg_attr(not(feature = "graphemes"), allow(dead_code))]
+    graphemes: bool,
+}
+
+impl<'a> StrInput<'a> {
+    /// Start at line 1, column 1, counting columns in `char`s
+    pub fn new(source: &'a str) -> Self {
+        StrInput {
+            source,
+            offset: 0,
+            line: 1,
+            column: 1,
+            graphemes: false,
+        }
+    }
+
+    /// Start at line 1, column 1, counting columns in grapheme clusters
+    #[cfg(feature = "graphemes")]
+    pub fn with_graphemes(source: &'a str) -> Self {
+        StrInput {
+            graphemes: true,
+            ..StrInput::new(source)
+        }
+    }
+
+    /// The 1-based line of the next character
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
+    /// The 1-based column of the next character
+    pub fn column(&self) -> usize {
+        self.column
+    }
+
+    /// The byte offset of the next character from the start of the source
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// The 1-based column of the first non-blank character on the current line,
+    /// i.e. the line's indentation, counting spaces and tabs as one column each
+    pub fn line_indent(&self) -> usize {
+        let line_start = self.source[..self.offset]
+            .rfind('\n')
+            .map_or(0, |newline| newline + 1);
+        let blanks = self.source[line_start..]
+            .chars()
+            .take_while(|c| *c == ' ' || *c == '\t')
+            .count();
+        blanks + 1
+    }
+
+    /// The input that has not been consumed yet
+    pub fn remaining(&self) -> &'a str {
+        &self.source[self.offset..]
+    }
+
+    /// Whether a new column starts at `offset`, i.e. whether it is a grapheme
+    /// boundary when counting graphemes
+    fn starts_column(&self, offset: usize) -> bool {
+        #[cfg(feature = "graphemes")]
+        if self.graphemes {
+            let mut cursor =
+                unicode_segmentation::GraphemeCursor::new(offset, self.source.len(), true);
+            return cursor.is_boundary(self.source, 0).unwrap_or(true);
+        }
+        let _ = offset;
+        true
+    }
+}
+
+impl PartialEq for StrInput<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        self.remaining() == other.remaining()
+    }
+}
+
+impl Input for StrInput<'_> {
+    type Item = char;
+
+    fn uncons(&self) -> Option<(Self::Item, Self)> {
+        let c = self.remaining().chars().next()?;
+        let offset = self.offset + c.len_utf8();
+        let (line, column) = if c == '\n' {
+            (self.line + 1, 1)
+        } else if self.starts_column(offset) {
+            (self.line, self.column + 1)
+        } else {
+            (self.line, self.column)
+        };
+        let remaining = StrInput {
+            offset,
+            line,
+            column,
+            ..self.clone()
+        };
+        Some((c, remaining))
+    }
+
+    fn uncons_n(&self, n: usize) -> Option<(Self, Self)> {
+        let mut remaining = self.clone();
+        for _ in 0..n {
+            remaining = remaining.uncons()?.1;
+        }
+        let prefix = StrInput {
+            source: &self.source[..remaining.offset],
+            ..self.clone()
+        };
+        Some((prefix, remaining))
+    }
+
+    fn consumed_since(&self, earlier: &Self) -> Option<Self> {
+        let same_source = std::ptr::eq(self.source, earlier.source);
+        (same_source && earlier.offset <= self.offset).then(|| StrInput {
+            source: &self.source[..self.offset],
+            ..earlier.clone()
+        })
+    }
+
+    fn len(&self) -> Option<usize> {
+        Some(self.source.len() - self.offset)
+    }
+
+    fn same_position(&self, other: &Self) -> bool {
+        self.remaining().len() == other.remaining().len()
+    }
+
+    fn position_key(&self) -> Option<(usize, usize, usize)> {
+        self.remaining().position_key()
+    }
+}
+
+impl Positioned for StrInput<'_> {
+    fn position(&self) -> usize {
+        self.offset
+    }
+}
+
+/// A string input that skips insignificant whitespace: `uncons` passes over any
+/// leading whitespace before returning the next non-whitespace character.
+///
+/// Parsers written against it, such as `token`, `tokens` or `satisfy`, then
+/// never see whitespace, and trailing whitespace counts as the end of input.
+/// Whitespace-sensitive regions like string literals have to bypass it, so
+/// parse those with `raw`, which runs a `&str` parser on the underlying input.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct IgnoreWs<'a> {
+    input: &'a str,
+}
+
+impl<'a> IgnoreWs<'a> {
+    /// Wrap a string, skipping whitespace anywhere in it
+    pub fn new(input: &'a str) -> Self {
+        IgnoreWs { input }
+    }
+
+    /// The underlying input that has not been consumed yet, including any
+    /// leading whitespace
+    pub fn remaining(&self) -> &'a str {
+        self.input
+    }
+}
+
+impl Input for IgnoreWs<'_> {
+    type Item = char;
+
+    fn uncons(&self) -> Option<(Self::Item, Self)> {
+        let trimmed = self.input.trim_start();
+        let c = trimmed.chars().next()?;
+        Some((c, IgnoreWs::new(&trimmed[c.len_utf8()..])))
+    }
+
+    fn uncons_n(&self, n: usize) -> Option<(Self, Self)> {
+        let mut remaining = *self;
+        for _ in 0..n {
+            remaining = remaining.uncons()?.1;
+        }
+        let consumed = self.input.len() - remaining.input.len();
+        Some((IgnoreWs::new(&self.input[..consumed]), remaining))
+    }
+
+    fn consumed_since(&self, earlier: &Self) -> Option<Self> {
+        Input::consumed_since(&self.input, &earlier.input).map(IgnoreWs::new)
+    }
+
+    fn len(&self) -> Option<usize> {
+        Some(self.input.len())
+    }
+
+    fn same_position(&self, other: &Self) -> bool {
+        self.input.len() == other.input.len()
+    }
+
+    fn position_key(&self) -> Option<(usize, usize, usize)> {
+        self.input.position_key()
+    }
 }
@@ -4,14 +4,32 @@
 //! input stream types, not just text. You can parse HTML DOM, JSON structures, or
 //! any type that implements the required iterator traits.
 
+pub mod bytes;
 pub mod combinators;
+#[cfg(feature = "diagnostics")]
+pub mod diagnostics;
 pub mod error;
+pub mod formats;
+pub mod indent;
 pub mod input;
+pub mod ops;
 pub mod parser;
+pub mod recovery;
+pub mod stateful;
 pub mod text;
 
 pub use combinators::*;
-pub use error::{ParseError, ParseResult};
-pub use input::Input;
-pub use parser::{Fail, Parser, Pure, fail, map2, map3, pure, tuple2, tuple3};
+#[cfg(feature = "diagnostics")]
+pub use diagnostics::report;
+pub use error::{ParseError, ParseResult, ParseResultExt, furthest_error_report};
+pub use indent::{Block, Indented, SameLine, block, indented, same_line};
+pub use input::{DequeInput, IgnoreWs, Input, IterInput, Located, Positioned, Span, StrInput};
+pub use ops::Ops;
+pub use parser::{
+    ApplyFn, Fail, NestedTuple, Parsed, Parser, ParserTuple, Pure, Trace, Tuple, fail, map2, map3,
+    parse_complete, parse_or_report, pure, tuple, tuple2, tuple3,
+};
+pub use recovery::{
+    Diagnostics, Recover, RecoverWith, parse_with_diagnostics, recover, recover_with,
+};
 pub use text::*;
@@ -0,0 +1,105 @@
+//! Operator overloads for concise grammar definitions.
+//!
+//! Operators cannot be implemented directly on every `Parser` because of Rust's
+//! coherence rules, so parsers are first wrapped with [`Parser::ops`]:
+//!
+//! - `a | b` tries `a`, then `b` (same as `a.or(b)`)
+//! - `a + b` keeps both results as a tuple (same as `a.and(b)`)
+//! - `a >> b` keeps only the right result (same as `b.preceded_by(a)`)
+//! - `a << b` keeps only the left result (same as `a.skip(b)`)
+//!
+//! The right-hand side of `|` may be any parser; the sequencing operators need
+//! both sides wrapped. Every operator returns another `Ops`, so chains compose.
+//!
+//! Operators follow Rust's precedence, not grammar intuition: `+` binds tighter
+//! than `>>` and `<<`, which bind tighter than `|`. So `a >> b | c` means
+//! `(a >> b) | c`, and `a + b >> c` means `(a + b) >> c`. Add parentheses when in
+//! doubt.
+
+use crate::parser::{And, Or, PrecededBy, Skip};
+use crate::{Input, ParseResult, Parser};
+use std::marker::PhantomData;
+use std::ops::{Add, BitOr, Shl, Shr};
+
+/// A parser wrapper that enables operator syntax
+pub struct Ops<P, I, T> {
+    parser: P,
+    _phantom: PhantomData<(I, T)>,
+}
+
+impl<P, I, T> Ops<P, I, T> {
+    /// Wrap a parser to enable operator syntax
+    pub fn new(parser: P) -> Self {
+        Ops {
+            parser,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Unwrap the underlying parser
+    pub fn into_inner(self) -> P {
+        self.parser
+    }
+}
+
+impl<P, I, T> Parser<I, T> for Ops<P, I, T>
+where
+    I: Input,
+    P: Parser<I, T>,
+{
+    fn parse(&self, input: I) -> ParseResult<I, T> {
+        self.parser.parse(input)
+    }
+}
+
+impl<P, Q, I, T> BitOr<Q> for Ops<P, I, T>
+where
+    I: Input,
+    P: Parser<I, T>,
+    Q: Parser<I, T>,
+{
+    type Output = Ops<Or<P, Q>, I, T>;
+
+    fn bitor(self, other: Q) -> Self::Output {
+        Ops::new(self.parser.or(other))
+    }
+}
+
+impl<P, Q, I, T, U> Add<Ops<Q, I, U>> for Ops<P, I, T>
+where
+    I: Input,
+    P: Parser<I, T>,
+    Q: Parser<I, U>,
+{
+    type Output = Ops<And<P, Q>, I, (T, U)>;
+
+    fn add(self, other: Ops<Q, I, U>) -> Self::Output {
+        Ops::new(self.parser.and(other.parser))
+    }
+}
+
+impl<P, Q, I, T, U> Shr<Ops<Q, I, U>> for Ops<P, I, T>
+where
+    I: Input,
+    P: Parser<I, T>,
+    Q: Parser<I, U>,
+{
+    type Output = Ops<PrecededBy<P, Q, U, T>, I, U>;
+
+    fn shr(self, other: Ops<Q, I, U>) -> Self::Output {
+        Ops::new(other.parser.preceded_by(self.parser))
+    }
+}
+
+impl<P, Q, I, T, U> Shl<Ops<Q, I, U>> for Ops<P, I, T>
+where
+    I: Input,
+    P: Parser<I, T>,
+    Q: Parser<I, U>,
+{
+    type Output = Ops<Skip<P, Q, T, U>, I, T>;
+
+    fn shl(self, other: Ops<Q, I, U>) -> Self::Output {
+        Ops::new(self.parser.skip(other.parser))
+    }
+}
@@ -1,7 +1,12 @@
 //! Core Parser trait and Applicative/Monadic combinators.
 
+use crate::combinators::{Complete, Not, Peek, complete, not, peek};
+use crate::error::furthest_error_report;
+use crate::input::{Mark, consumed};
+use crate::ops::Ops;
 use crate::{Input, ParseError, ParseResult};
 use std::marker::PhantomData;
+use std::ops::{Range, RangeFrom, RangeFull, RangeInclusive, RangeTo, RangeToInclusive};
 
 /// A parser that consumes input of type `I` and produces a value of type `T`.
 ///
@@ -11,6 +16,32 @@ pub trait Parser<I: Input, T> {
     /// Run the parser on the given input
     fn parse(&self, input: I) -> ParseResult<I, T>;
 
+    /// Run the parser, handing the original input back alongside the error if it fails
+    ///
+    /// Backtracking combinators such as `or`, `optional` and the repetitions use this
+    /// instead of keeping their own copy of the input before every attempt. The
+    /// default keeps that copy; parsers that only look at the next item, such as
+    /// `token` and `satisfy`, override it so that a success copies nothing.
+    fn parse_or_restore(&self, input: I) -> Result<(T, I), (ParseError<I>, I)> {
+        let original = input.clone();
+        self.parse(input).map_err(|err| (err, original))
+    }
+
+    /// Run the parser, returning the value and remaining input as a named struct
+    fn run(&self, input: I) -> Result<Parsed<I, T>, ParseError<I>> {
+        self.parse(input)
+            .map(|(value, remaining)| Parsed { value, remaining })
+    }
+
+    /// Borrow this parser, so combinators can be built from it without consuming it
+    /// Works through the blanket implementation of `Parser` for `&P`.
+    fn by_ref(&self) -> &Self
+    where
+        Self: Sized,
+    {
+        self
+    }
+
     /// Applicative map: transform the result of a successful parse
     fn map<U, F>(self, f: F) -> Map<Self, F, T, U>
     where
@@ -24,6 +55,99 @@ pub trait Parser<I: Input, T> {
         }
     }
 
+    /// Collect a result of characters, such as the `Vec<char>` from `many1`, into a `String`
+    fn collect_string(self) -> Map<Self, fn(T) -> String, T, String>
+    where
+        Self: Sized,
+        T: IntoIterator<Item = char>,
+    {
+        self.map(|chars| chars.into_iter().collect())
+    }
+
+    /// Transform the result together with the input it was parsed from
+    ///
+    /// The second argument is the consumed prefix of the input, e.g. the original
+    /// spelling of a number. Computing it walks over the consumed items once.
+    /// Fails if the input type cannot represent a prefix (see `Input::uncons_n`).
+    fn map_with_input<U, F>(self, f: F) -> MapWithInput<Self, F, T, U>
+    where
+        Self: Sized,
+        F: Fn(T, I) -> U,
+    {
+        MapWithInput {
+            parser: self,
+            f,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Convert the result into another type via `Into`
+    fn map_into<U>(self) -> MapInto<Self, T, U>
+    where
+        Self: Sized,
+        T: Into<U>,
+    {
+        MapInto {
+            parser: self,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Flatten a nested optional result, `Option<Option<T>>` into `Option<T>`
+    fn flatten(self) -> Flatten<Self>
+    where
+        Self: Sized,
+    {
+        Flatten { parser: self }
+    }
+
+    /// Turn a parser producing `Result<T, E>` into one producing `T`, failing with
+    /// the displayed `Err` as a message at the position where this parser started
+    fn flatten_result<E>(self) -> FlattenResult<Self, E>
+    where
+        Self: Sized,
+    {
+        FlattenResult {
+            parser: self,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Validate and transform the result, failing with the `Err` message if `f` rejects it
+    ///
+    /// The error points at the input where this parser started, i.e. at the rejected
+    /// value itself. A failure raised through `bind` instead points after the value,
+    /// because the next parser only sees the remaining input.
+    fn verify_map<U, F>(self, f: F) -> VerifyMap<Self, F, T>
+    where
+        Self: Sized,
+        F: Fn(T) -> Result<U, String>,
+    {
+        VerifyMap {
+            parser: self,
+            f,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Transform the result with a fallible function, nom's `map_res`
+    ///
+    /// Like `verify_map`, but accepts any `Display` error, such as the `ParseIntError`
+    /// from `str::parse`. The error message is `e.to_string()` and points at the
+    /// input where this parser started.
+    fn map_res<U, E, F>(self, f: F) -> MapRes<Self, F, T>
+    where
+        Self: Sized,
+        F: Fn(T) -> Result<U, E>,
+        E: std::fmt::Display,
+    {
+        MapRes {
+            parser: self,
+            f,
+            _phantom: PhantomData,
+        }
+    }
+
     /// Applicative sequence: parse two things in sequence, keeping both results
     fn and<U, P>(self, other: P) -> And<Self, P>
     where
@@ -62,6 +186,83 @@ pub trait Parser<I: Input, T> {
         }
     }
 
+    /// Parse `left`, this, then `right`, keeping only the result of this
+    /// The method form of `between`, e.g. `integer().delimited_by(char('('), char(')'))`.
+    fn delimited_by<U, V, L, R>(self, left: L, right: R) -> Skip<PrecededBy<L, Self, T, U>, R, T, V>
+    where
+        Self: Sized,
+        L: Parser<I, U>,
+        R: Parser<I, V>,
+    {
+        self.preceded_by(left).skip(right)
+    }
+
+    /// Parse this, then that if present, keeping only the result of this
+    /// E.g. `integer().opt_skip(char(';'))` accepts `1;` and `1` alike.
+    fn opt_skip<U, P>(self, other: P) -> Skip<Self, Optional<P>, T, Option<U>>
+    where
+        Self: Sized,
+        P: Parser<I, U>,
+    {
+        self.skip(other.optional())
+    }
+
+    /// Parse that if present, then this, keeping only the result of this
+    /// E.g. `unsigned().opt_preceded_by(char('+'))` accepts `+1` and `1` alike.
+    fn opt_preceded_by<U, P>(self, other: P) -> PrecededBy<Optional<P>, Self, T, Option<U>>
+    where
+        Self: Sized,
+        P: Parser<I, U>,
+    {
+        self.preceded_by(other.optional())
+    }
+
+    /// Parse this, then check without consuming anything that `other` does not match
+    /// E.g. `char('=').not_followed_by(char('='))` matches `=` but not the start of `==`.
+    fn not_followed_by<U, P>(self, other: P) -> Skip<Self, Not<P, U>, T, ()>
+    where
+        Self: Sized,
+        P: Parser<I, U>,
+    {
+        self.skip(not(other))
+    }
+
+    /// Parse this, then transform the result with `then` if `lookahead` matches what
+    /// follows, or with `otherwise` if it does not. The lookahead consumes nothing.
+    ///
+    /// E.g. `unsigned().map_if(char('%'), |n| n as f64 / 100.0, |n| n as f64)` reads
+    /// `50%` as `0.5` and leaves the `%` for the next parser.
+    fn map_if<U, V, Q, F, G>(
+        self,
+        lookahead: Q,
+        then: F,
+        otherwise: G,
+    ) -> MapIf<Self, Q, F, G, T, V>
+    where
+        Self: Sized,
+        Q: Parser<I, V>,
+        F: Fn(T) -> U,
+        G: Fn(T) -> U,
+    {
+        MapIf {
+            parser: self,
+            lookahead,
+            then,
+            otherwise,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Parse this, then check without consuming anything that `other` matches
+    /// If `other` fails its error is returned; otherwise its input is left for the next parser.
+    fn followed_by<U, P>(self, other: P) -> Skip<Self, Peek<P>, T, U>
+    where
+        Self: Sized,
+        P: Parser<I, U>,
+    {
+        self.skip(peek(other))
+    }
+
     /// Monadic bind: parse this, then use the result to determine the next parser
     fn bind<U, F, P>(self, f: F) -> Bind<Self, F, T, U>
     where
@@ -76,6 +277,25 @@ pub trait Parser<I: Input, T> {
         }
     }
 
+    /// Apply a function to the results of a chain of `and`, one argument per result
+    ///
+    /// `a.and(b).and(c).apply(|a, b, c| ...)` flattens the nested `((a, b), c)` result,
+    /// for chains of 2 to 5 parsers. Unlike `map`, the closure's parameter types are
+    /// not known while its body is checked, so calling methods on a parameter needs
+    /// an annotation such as `|name: &str, n| ...`.
+    fn apply<Flat, R, F>(self, f: F) -> Apply<Self, F, T, Flat>
+    where
+        Self: Sized,
+        T: NestedTuple<Flat>,
+        F: ApplyFn<Flat, R>,
+    {
+        Apply {
+            parser: self,
+            f,
+            _phantom: PhantomData,
+        }
+    }
+
     /// Alternative: try this parser, if it fails try the other
     fn or<P>(self, other: P) -> Or<Self, P>
     where
@@ -88,6 +308,136 @@ pub trait Parser<I: Input, T> {
         }
     }
 
+    /// Alternative chosen from the error: if this parser fails, build the fallback with `f`
+    /// If the fallback also fails, both errors are reported, as with `or`.
+    fn or_else<P, F>(self, f: F) -> OrElse<Self, F>
+    where
+        Self: Sized,
+        F: Fn(&ParseError<I>) -> P,
+        P: Parser<I, T>,
+    {
+        OrElse { parser: self, f }
+    }
+
+    /// Transform the error of a failed parse, leaving successes untouched
+    fn map_err<F>(self, f: F) -> MapErr<Self, F>
+    where
+        Self: Sized,
+        F: Fn(ParseError<I>) -> ParseError<I>,
+    {
+        MapErr { parser: self, f }
+    }
+
+    /// Wrap any error from this parser with a context message
+    fn with_context(self, context: impl Into<String>) -> WithContext<Self>
+    where
+        Self: Sized,
+    {
+        WithContext {
+            parser: self,
+            context: context.into(),
+        }
+    }
+
+    /// Replace any error from this parser with `message`, at the input where it started
+    ///
+    /// A blunt override for user-facing messages: the original error is discarded,
+    /// however far the parser got. `with_context` keeps the original error and
+    /// prefixes it instead, and `named` records the parser's name alongside it.
+    fn expect(self, message: impl Into<String>) -> Expect<Self>
+    where
+        Self: Sized,
+    {
+        Expect {
+            parser: self,
+            message: message.into(),
+        }
+    }
+
+    /// Treat running out of input as a definitive failure, method form of `complete()`
+    ///
+    /// Every nested `Incomplete` in the final error becomes `UnexpectedEof`, so the
+    /// same grammar serves batch and streaming callers. `or` already tries its right
+    /// side after an `Incomplete`, so wrapping a whole alternation only changes the
+    /// reported error. `eof()` never reports `Incomplete` and is unaffected.
+    fn complete(self) -> Complete<Self>
+    where
+        Self: Sized,
+    {
+        complete(self)
+    }
+
+    /// Require this parser to consume all remaining input, keeping its result
+    ///
+    /// The fluent counterpart to `parse_complete`: fails with an "unexpected trailing
+    /// input" error at the leftover input instead of needing `.skip(eof())`.
+    fn end(self) -> End<Self>
+    where
+        Self: Sized,
+    {
+        End { parser: self }
+    }
+
+    /// Parse this, also returning how much input it consumed, in units of `Input::len`
+    ///
+    /// A lightweight alternative to `spanned` for inputs without absolute positions,
+    /// e.g. for progress reporting. Fails with an error if the input does not know
+    /// its length.
+    fn count_consumed(self) -> CountConsumed<Self>
+    where
+        Self: Sized,
+    {
+        CountConsumed { parser: self }
+    }
+
+    /// Wrap this parser to enable operator syntax (`|`, `+`, `>>`, `<<`)
+    /// See the `ops` module for the meaning and precedence of each operator.
+    fn ops(self) -> Ops<Self, I, T>
+    where
+        Self: Sized,
+    {
+        Ops::new(self)
+    }
+
+    /// Label this parser for debugging
+    ///
+    /// With the `trace` feature enabled, every attempt prints the label and the
+    /// upcoming input to stderr, followed by whether it succeeded and what input
+    /// remains. Nested traced parsers are indented. Without the feature this is a
+    /// transparent wrapper.
+    fn trace(self, label: impl Into<String>) -> Trace<Self>
+    where
+        Self: Sized,
+    {
+        Trace {
+            parser: self,
+            label: label.into(),
+        }
+    }
+
+    /// Run `f` on each successful result, passing the result through unchanged
+    /// Unlike `trace`, this is always active, e.g. for counting tokens or reporting progress.
+    fn inspect<F>(self, f: F) -> Inspect<Self, F>
+    where
+        Self: Sized,
+        F: Fn(&T),
+    {
+        Inspect { parser: self, f }
+    }
+
+    /// Name this parser, so errors bubbling up through it record the chain of
+    /// names, e.g. `in number > digit: unexpected end of input`
+    /// A lighter-weight alternative to `with_context` for debugging deep grammars.
+    fn named(self, name: impl Into<String>) -> Named<Self>
+    where
+        Self: Sized,
+    {
+        Named {
+            parser: self,
+            name: name.into(),
+        }
+    }
+
     /// Make this parser optional (returns Some(result) or None)
     fn optional(self) -> Optional<Self>
     where
@@ -96,24 +446,53 @@ pub trait Parser<I: Input, T> {
         Optional { parser: self }
     }
 
-    /// Parse zero or more occurrences
-    fn many(self) -> Many<Self>
+    /// Make this parser optional, yielding `default` instead of `None` when it fails
+    fn or_default(self, default: T) -> OrDefault<Self, T>
     where
         Self: Sized,
         T: Clone,
+    {
+        OrDefault {
+            parser: self,
+            default,
+        }
+    }
+
+    /// Parse zero or more occurrences, stopping at the first one that consumes no input
+    /// Equivalent to `repeat(..)`
+    fn many(self) -> Many<Self>
+    where
+        Self: Sized,
     {
         Many { parser: self }
     }
 
     /// Parse one or more occurrences
+    /// Equivalent to `repeat(1..)`
     fn many1(self) -> Many1<Self>
     where
         Self: Sized,
-        T: Clone,
     {
         Many1 { parser: self }
     }
 
+    /// Parse a number of occurrences within `range`: `..` is `many`, `1..` is `many1`,
+    /// `3` is exactly three and `2..=4` is between two and four
+    ///
+    /// Stops once the upper bound is reached, leaving further occurrences unconsumed.
+    /// With too few occurrences, fails with the error from the first one that failed.
+    fn repeat<R: RepeatRange>(self, range: R) -> Repeat<Self>
+    where
+        Self: Sized,
+    {
+        let (min, max) = range.bounds();
+        Repeat {
+            parser: self,
+            min,
+            max,
+        }
+    }
+
     /// Fold over zero or more occurrences with an accumulator
     fn fold_many0<A, F>(self, init: A, f: F) -> FoldMany0<Self, A, F, T>
     where
@@ -141,6 +520,69 @@ pub trait Parser<I: Input, T> {
             _phantom: PhantomData,
         }
     }
+
+    /// Fold over between `min` and `max` occurrences with an accumulator
+    ///
+    /// Stops once `max` is reached, and fails with the error from the first failed
+    /// occurrence if fewer than `min` matched.
+    fn fold_many_m_n<A, F>(self, min: usize, max: usize, init: A, f: F) -> FoldManyMN<Self, A, F, T>
+    where
+        Self: Sized,
+        F: FnMut(A, T) -> A,
+    {
+        FoldManyMN {
+            parser: self,
+            min,
+            max,
+            init,
+            f,
+            _phantom: PhantomData,
+        }
+    }
+}
+
+/// A borrowed parser is itself a parser, so `p.by_ref().many()` leaves `p` usable
+impl<I, T, P> Parser<I, T> for &P
+where
+    I: Input,
+    P: Parser<I, T> + ?Sized,
+{
+    fn parse(&self, input: I) -> ParseResult<I, T> {
+        (**self).parse(input)
+    }
+
+    fn parse_or_restore(&self, input: I) -> Result<(T, I), (ParseError<I>, I)> {
+        (**self).parse_or_restore(input)
+    }
+}
+
+/// The outcome of a successful parse: the parsed value and the remaining input
+#[derive(Debug, Clone, PartialEq)]
+pub struct Parsed<I, T> {
+    pub value: T,
+    pub remaining: I,
+}
+
+impl<I, T> Parsed<I, T> {
+    /// The parsed value
+    pub fn value(&self) -> &T {
+        &self.value
+    }
+
+    /// The input left over after parsing
+    pub fn remaining(&self) -> &I {
+        &self.remaining
+    }
+
+    /// Discard the remaining input, keeping only the value
+    pub fn into_value(self) -> T {
+        self.value
+    }
+
+    /// Split into the `(value, remaining)` tuple used by `ParseResult`
+    pub fn into_parts(self) -> (T, I) {
+        (self.value, self.remaining)
+    }
 }
 
 // Applicative combinators
@@ -152,74 +594,446 @@ pub struct Map<P, F, T, U> {
     _phantom: PhantomData<(T, U)>,
 }
 
-impl<I, T, U, P, F> Parser<I, U> for Map<P, F, T, U>
+impl<I, T, U, P, F> Parser<I, U> for Map<P, F, T, U>
+where
+    I: Input,
+    P: Parser<I, T>,
+    F: Fn(T) -> U,
+{
+    fn parse(&self, input: I) -> ParseResult<I, U> {
+        self.parser
+            .parse(input)
+            .map(|(result, remaining)| ((self.f)(result), remaining))
+    }
+
+    fn parse_or_restore(&self, input: I) -> Result<(U, I), (ParseError<I>, I)> {
+        self.parser
+            .parse_or_restore(input)
+            .map(|(result, remaining)| ((self.f)(result), remaining))
+    }
+}
+
+/// MapIf combinator - picks a transform depending on what follows
+pub struct MapIf<P, Q, F, G, T, V> {
+    parser: P,
+    lookahead: Q,
+    then: F,
+    otherwise: G,
+    _phantom: PhantomData<(T, V)>,
+}
+
+impl<I, T, U, V, P, Q, F, G> Parser<I, U> for MapIf<P, Q, F, G, T, V>
+where
+    I: Input,
+    P: Parser<I, T>,
+    Q: Parser<I, V>,
+    F: Fn(T) -> U,
+    G: Fn(T) -> U,
+{
+    fn parse(&self, input: I) -> ParseResult<I, U> {
+        let (result, remaining) = self.parser.parse(input)?;
+        let value = if self.lookahead.parse(remaining.clone()).is_ok() {
+            (self.then)(result)
+        } else {
+            (self.otherwise)(result)
+        };
+        Ok((value, remaining))
+    }
+}
+
+/// MapWithInput combinator - transforms parser output along with the consumed input
+pub struct MapWithInput<P, F, T, U> {
+    parser: P,
+    f: F,
+    _phantom: PhantomData<(T, U)>,
+}
+
+impl<I, T, U, P, F> Parser<I, U> for MapWithInput<P, F, T, U>
+where
+    I: Input,
+    P: Parser<I, T>,
+    F: Fn(T, I) -> U,
+{
+    fn parse(&self, input: I) -> ParseResult<I, U> {
+        let (result, remaining) = self.parser.parse(input.clone())?;
+        match consumed(&input, &remaining) {
+            Some(consumed) => Ok(((self.f)(result, consumed), remaining)),
+            None => Err(ParseError::message(
+                "input does not support taking a prefix",
+                input,
+            )),
+        }
+    }
+}
+
+/// MapInto combinator - converts parser output with `Into`
+pub struct MapInto<P, T, U> {
+    parser: P,
+    _phantom: PhantomData<(T, U)>,
+}
+
+impl<I, T, U, P> Parser<I, U> for MapInto<P, T, U>
+where
+    I: Input,
+    P: Parser<I, T>,
+    T: Into<U>,
+{
+    fn parse(&self, input: I) -> ParseResult<I, U> {
+        self.parser
+            .parse(input)
+            .map(|(result, remaining)| (result.into(), remaining))
+    }
+}
+
+/// Flatten combinator - collapses `Option<Option<T>>` into `Option<T>`
+pub struct Flatten<P> {
+    parser: P,
+}
+
+impl<I, T, P> Parser<I, Option<T>> for Flatten<P>
+where
+    I: Input,
+    P: Parser<I, Option<Option<T>>>,
+{
+    fn parse(&self, input: I) -> ParseResult<I, Option<T>> {
+        self.parser
+            .parse(input)
+            .map(|(result, remaining)| (result.flatten(), remaining))
+    }
+}
+
+/// FlattenResult combinator - fails when the parsed value is an `Err`
+pub struct FlattenResult<P, E> {
+    parser: P,
+    _phantom: PhantomData<E>,
+}
+
+impl<I, T, E, P> Parser<I, T> for FlattenResult<P, E>
+where
+    I: Input,
+    P: Parser<I, Result<T, E>>,
+    E: std::fmt::Display,
+{
+    fn parse(&self, input: I) -> ParseResult<I, T> {
+        match self.parser.parse(input.clone())? {
+            (Ok(result), remaining) => Ok((result, remaining)),
+            (Err(error), _) => Err(ParseError::message(error.to_string(), input)),
+        }
+    }
+}
+
+/// VerifyMap combinator - validates and transforms a result in one step
+pub struct VerifyMap<P, F, T> {
+    parser: P,
+    f: F,
+    _phantom: PhantomData<T>,
+}
+
+impl<I, T, U, P, F> Parser<I, U> for VerifyMap<P, F, T>
+where
+    I: Input,
+    P: Parser<I, T>,
+    F: Fn(T) -> Result<U, String>,
+{
+    fn parse(&self, input: I) -> ParseResult<I, U> {
+        let (result, remaining) = self.parser.parse(input.clone())?;
+        match (self.f)(result) {
+            Ok(value) => Ok((value, remaining)),
+            Err(message) => Err(ParseError::message(message, input)),
+        }
+    }
+}
+
+/// MapRes combinator - transforms a result with a fallible function
+pub struct MapRes<P, F, T> {
+    parser: P,
+    f: F,
+    _phantom: PhantomData<T>,
+}
+
+impl<I, T, U, E, P, F> Parser<I, U> for MapRes<P, F, T>
+where
+    I: Input,
+    P: Parser<I, T>,
+    F: Fn(T) -> Result<U, E>,
+    E: std::fmt::Display,
+{
+    fn parse(&self, input: I) -> ParseResult<I, U> {
+        let (result, remaining) = self.parser.parse(input.clone())?;
+        match (self.f)(result) {
+            Ok(value) => Ok((value, remaining)),
+            Err(err) => Err(ParseError::message(err.to_string(), input)),
+        }
+    }
+}
+
+/// And combinator - parses two things in sequence, keeping both
+pub struct And<L, R> {
+    left: L,
+    right: R,
+}
+
+impl<I, T, U, L, R> Parser<I, (T, U)> for And<L, R>
+where
+    I: Input,
+    L: Parser<I, T>,
+    R: Parser<I, U>,
+{
+    fn parse(&self, input: I) -> ParseResult<I, (T, U)> {
+        let (left_result, input1) = self.left.parse(input)?;
+        let (right_result, input2) = self.right.parse(input1)?;
+        Ok(((left_result, right_result), input2))
+    }
+}
+
+/// Skip combinator - parse left then right, keep only left result
+pub struct Skip<L, R, T, U> {
+    left: L,
+    right: R,
+    _phantom: PhantomData<(T, U)>,
+}
+
+impl<I, T, U, L, R> Parser<I, T> for Skip<L, R, T, U>
+where
+    I: Input,
+    L: Parser<I, T>,
+    R: Parser<I, U>,
+{
+    fn parse(&self, input: I) -> ParseResult<I, T> {
+        let (left_result, input1) = self.left.parse(input)?;
+        let (_, input2) = self.right.parse(input1)?;
+        Ok((left_result, input2))
+    }
+}
+
+/// PrecededBy combinator - parse first then second, keep only second result
+pub struct PrecededBy<F, S, T, U> {
+    first: F,
+    second: S,
+    _phantom: PhantomData<(T, U)>,
+}
+
+impl<I, T, U, F, S> Parser<I, T> for PrecededBy<F, S, T, U>
+where
+    I: Input,
+    F: Parser<I, U>,
+    S: Parser<I, T>,
+{
+    fn parse(&self, input: I) -> ParseResult<I, T> {
+        let (_, input1) = self.first.parse(input)?;
+        self.second.parse(input1)
+    }
+}
+
+/// MapErr combinator - transforms parser errors
+pub struct MapErr<P, F> {
+    parser: P,
+    f: F,
+}
+
+impl<I, T, P, F> Parser<I, T> for MapErr<P, F>
+where
+    I: Input,
+    P: Parser<I, T>,
+    F: Fn(ParseError<I>) -> ParseError<I>,
+{
+    fn parse(&self, input: I) -> ParseResult<I, T> {
+        self.parser.parse(input).map_err(&self.f)
+    }
+}
+
+/// WithContext combinator - wraps parser errors with a context message
+pub struct WithContext<P> {
+    parser: P,
+    context: String,
+}
+
+impl<I, T, P> Parser<I, T> for WithContext<P>
+where
+    I: Input,
+    P: Parser<I, T>,
+{
+    fn parse(&self, input: I) -> ParseResult<I, T> {
+        self.parser
+            .parse(input)
+            .map_err(|error| ParseError::context(self.context.clone(), error))
+    }
+}
+
+/// End combinator - requires the parser to reach end of input
+pub struct End<P> {
+    parser: P,
+}
+
+impl<I, T, P> Parser<I, T> for End<P>
+where
+    I: Input,
+    P: Parser<I, T>,
+{
+    fn parse(&self, input: I) -> ParseResult<I, T> {
+        let (value, remaining) = self.parser.parse(input)?;
+        if remaining.is_empty() {
+            Ok((value, remaining))
+        } else {
+            Err(ParseError::message("unexpected trailing input", remaining))
+        }
+    }
+}
+
+/// CountConsumed combinator - pairs a result with the length of input consumed
+pub struct CountConsumed<P> {
+    parser: P,
+}
+
+impl<I, T, P> Parser<I, (T, usize)> for CountConsumed<P>
+where
+    I: Input,
+    P: Parser<I, T>,
+{
+    fn parse(&self, input: I) -> ParseResult<I, (T, usize)> {
+        let Some(before) = input.len() else {
+            return Err(ParseError::message(
+                "cannot count consumed input of unknown length",
+                input,
+            ));
+        };
+        let (value, remaining) = self.parser.parse(input)?;
+        match remaining.len() {
+            Some(after) => Ok(((value, before.saturating_sub(after)), remaining)),
+            None => Err(ParseError::message(
+                "cannot count consumed input of unknown length",
+                remaining,
+            )),
+        }
+    }
+}
+
+/// Expect combinator - replaces errors with a fixed message
+pub struct Expect<P> {
+    parser: P,
+    message: String,
+}
+
+impl<I, T, P> Parser<I, T> for Expect<P>
 where
     I: Input,
     P: Parser<I, T>,
-    F: Fn(T) -> U,
 {
-    fn parse(&self, input: I) -> ParseResult<I, U> {
+    fn parse(&self, input: I) -> ParseResult<I, T> {
         self.parser
-            .parse(input)
-            .map(|(result, remaining)| ((self.f)(result), remaining))
+            .parse(input.clone())
+            .map_err(|_| ParseError::message(self.message.clone(), input))
     }
 }
 
-/// And combinator - parses two things in sequence, keeping both
-pub struct And<L, R> {
-    left: L,
-    right: R,
+/// Named combinator - records its name on errors passing through it
+pub struct Named<P> {
+    parser: P,
+    name: String,
 }
 
-impl<I, T, U, L, R> Parser<I, (T, U)> for And<L, R>
+impl<I, T, P> Parser<I, T> for Named<P>
 where
     I: Input,
-    L: Parser<I, T>,
-    R: Parser<I, U>,
+    P: Parser<I, T>,
 {
-    fn parse(&self, input: I) -> ParseResult<I, (T, U)> {
-        let (left_result, input1) = self.left.parse(input)?;
-        let (right_result, input2) = self.right.parse(input1)?;
-        Ok(((left_result, right_result), input2))
+    fn parse(&self, input: I) -> ParseResult<I, T> {
+        self.parser
+            .parse(input)
+            .map_err(|error| ParseError::named(self.name.clone(), error))
     }
 }
 
-/// Skip combinator - parse left then right, keep only left result
-pub struct Skip<L, R, T, U> {
-    left: L,
-    right: R,
-    _phantom: PhantomData<(T, U)>,
+/// Inspect combinator - observes successful results without changing them
+pub struct Inspect<P, F> {
+    parser: P,
+    f: F,
 }
 
-impl<I, T, U, L, R> Parser<I, T> for Skip<L, R, T, U>
+impl<I, T, P, F> Parser<I, T> for Inspect<P, F>
 where
     I: Input,
-    L: Parser<I, T>,
-    R: Parser<I, U>,
+    P: Parser<I, T>,
+    F: Fn(&T),
 {
     fn parse(&self, input: I) -> ParseResult<I, T> {
-        let (left_result, input1) = self.left.parse(input)?;
-        let (_, input2) = self.right.parse(input1)?;
-        Ok((left_result, input2))
+        let (result, remaining) = self.parser.parse(input)?;
+        (self.f)(&result);
+        Ok((result, remaining))
     }
 }
 
-/// PrecededBy combinator - parse first then second, keep only second result
-pub struct PrecededBy<F, S, T, U> {
-    first: F,
-    second: S,
-    _phantom: PhantomData<(T, U)>,
+/// Trace combinator - reports parse attempts to stderr with the `trace` feature
+pub struct Trace<P> {
+    parser: P,
+    #[cfg_attr(not(feature = "trace"), allow(dead_code))]
+    label: String,
 }
 
-impl<I, T, U, F, S> Parser<I, T> for PrecededBy<F, S, T, U>
+impl<I, T, P> Parser<I, T> for Trace<P>
 where
     I: Input,
-    F: Parser<I, U>,
-    S: Parser<I, T>,
+    P: Parser<I, T>,
 {
+    #[cfg(not(feature = "trace"))]
     fn parse(&self, input: I) -> ParseResult<I, T> {
-        let (_, input1) = self.first.parse(input)?;
-        self.second.parse(input1)
+        self.parser.parse(input)
+    }
+
+    #[cfg(feature = "trace")]
+    fn parse(&self, input: I) -> ParseResult<I, T> {
+        let depth = trace::enter();
+        let indent = "  ".repeat(depth);
+        eprintln!("{indent}> {}: {}", self.label, trace::preview(&input));
+        let result = self.parser.parse(input);
+        match &result {
+            Ok((_, remaining)) => {
+                eprintln!(
+                    "{indent}< {}: ok, remaining {}",
+                    self.label,
+                    trace::preview(remaining)
+                )
+            }
+            Err(_) => eprintln!("{indent}< {}: failed", self.label),
+        }
+        trace::exit();
+        result
+    }
+}
+
+#[cfg(feature = "trace")]
+mod trace {
+    use crate::Input;
+    use std::cell::Cell;
+
+    /// How many items of input to show in each trace line
+    const PREVIEW_ITEMS: usize = 16;
+
+    thread_local! {
+        static DEPTH: Cell<usize> = const { Cell::new(0) };
+    }
+
+    /// Increase the nesting depth, returning the depth before entering
+    pub(super) fn enter() -> usize {
+        DEPTH.with(|depth| depth.replace(depth.get() + 1))
+    }
+
+    pub(super) fn exit() {
+        DEPTH.with(|depth| depth.set(depth.get().saturating_sub(1)));
+    }
+
+    /// Format the first few items of the input
+    pub(super) fn preview<I: Input>(input: &I) -> String {
+        let mut items = Vec::new();
+        let mut rest = input.clone();
+        while let Some((item, remaining)) = rest.uncons() {
+            if items.len() == PREVIEW_ITEMS {
+                return format!("[{}, ...]", items.join(", "));
+            }
+            items.push(format!("{:?}", item));
+            rest = remaining;
+        }
+        format!("[{}]", items.join(", "))
     }
 }
 
@@ -248,6 +1062,10 @@ where
 // Choice combinators
 
 /// Or combinator - try left, if it fails try right
+///
+/// The left branch hands the input back when it fails, so the right branch starts
+/// from the original position. Only a left branch that may consume before failing
+/// keeps a copy of the input, see `Parser::parse_or_restore`.
 pub struct Or<L, R> {
     left: L,
     right: R,
@@ -260,14 +1078,50 @@ where
     R: Parser<I, T>,
 {
     fn parse(&self, input: I) -> ParseResult<I, T> {
-        match self.left.parse(input.clone()) {
+        match self.left.parse_or_restore(input) {
             Ok(result) => Ok(result),
-            Err(left_err) => match self.right.parse(input) {
+            Err((left_err, input)) => match self.right.parse(input) {
                 Ok(result) => Ok(result),
                 Err(right_err) => Err(ParseError::many(vec![left_err, right_err])),
             },
         }
     }
+
+    fn parse_or_restore(&self, input: I) -> Result<(T, I), (ParseError<I>, I)> {
+        match self.left.parse_or_restore(input) {
+            Ok(result) => Ok(result),
+            Err((left_err, input)) => match self.right.parse_or_restore(input) {
+                Ok(result) => Ok(result),
+                Err((right_err, input)) => {
+                    Err((ParseError::many(vec![left_err, right_err]), input))
+                }
+            },
+        }
+    }
+}
+
+/// OrElse combinator - tries a parser, then a fallback built from its error
+pub struct OrElse<P, F> {
+    parser: P,
+    f: F,
+}
+
+impl<I, T, P, Q, F> Parser<I, T> for OrElse<P, F>
+where
+    I: Input,
+    P: Parser<I, T>,
+    F: Fn(&ParseError<I>) -> Q,
+    Q: Parser<I, T>,
+{
+    fn parse(&self, input: I) -> ParseResult<I, T> {
+        match self.parser.parse_or_restore(input) {
+            Ok(result) => Ok(result),
+            Err((err, input)) => match (self.f)(&err).parse(input) {
+                Ok(result) => Ok(result),
+                Err(fallback_err) => Err(ParseError::many(vec![err, fallback_err])),
+            },
+        }
+    }
 }
 
 /// Optional combinator - makes a parser optional
@@ -281,9 +1135,37 @@ where
     P: Parser<I, T>,
 {
     fn parse(&self, input: I) -> ParseResult<I, Option<T>> {
-        match self.parser.parse(input.clone()) {
+        self.parse_or_restore(input).map_err(|(err, _)| err)
+    }
+
+    fn parse_or_restore(&self, input: I) -> Result<(Option<T>, I), (ParseError<I>, I)> {
+        match self.parser.parse_or_restore(input) {
             Ok((result, remaining)) => Ok((Some(result), remaining)),
-            Err(_) => Ok((None, input)),
+            Err((_, input)) => Ok((None, input)),
+        }
+    }
+}
+
+/// OrDefault combinator - falls back to a default value on failure
+pub struct OrDefault<P, T> {
+    parser: P,
+    default: T,
+}
+
+impl<I, T, P> Parser<I, T> for OrDefault<P, T>
+where
+    I: Input,
+    P: Parser<I, T>,
+    T: Clone,
+{
+    fn parse(&self, input: I) -> ParseResult<I, T> {
+        self.parse_or_restore(input).map_err(|(err, _)| err)
+    }
+
+    fn parse_or_restore(&self, input: I) -> Result<(T, I), (ParseError<I>, I)> {
+        match self.parser.parse_or_restore(input) {
+            Ok(result) => Ok(result),
+            Err((_, input)) => Ok((self.default.clone(), input)),
         }
     }
 }
@@ -291,7 +1173,12 @@ where
 // Repetition combinators
 
 /// Generic fold over zero or more parser repetitions
-fn fold_many0<I, T, P, A, F>(parser: &P, init: A, mut f: F, mut input: I) -> ParseResult<I, A>
+pub(crate) fn fold_many0<I, T, P, A, F>(
+    parser: &P,
+    init: A,
+    mut f: F,
+    mut input: I,
+) -> ParseResult<I, A>
 where
     I: Input,
     P: Parser<I, T>,
@@ -299,15 +1186,17 @@ where
 {
     let mut acc = init;
     loop {
-        match parser.parse(input.clone()) {
+        let mark = Mark::new(&input);
+        match parser.parse_or_restore(input) {
+            // A zero-width success would repeat forever, so it ends the repetition
+            Ok((_, remaining)) if !mark.progressed_to(&remaining) => return Ok((acc, remaining)),
             Ok((result, remaining)) => {
                 acc = f(acc, result);
                 input = remaining;
             }
-            Err(_) => break,
+            Err((_, restored)) => return Ok((acc, restored)),
         }
     }
-    Ok((acc, input))
 }
 
 /// Generic fold over one or more parser repetitions
@@ -331,18 +1220,9 @@ impl<I, T, P> Parser<I, Vec<T>> for Many<P>
 where
     I: Input,
     P: Parser<I, T>,
-    T: Clone,
 {
     fn parse(&self, input: I) -> ParseResult<I, Vec<T>> {
-        fold_many0(
-            &self.parser,
-            Vec::new(),
-            |mut acc, item| {
-                acc.push(item);
-                acc
-            },
-            input,
-        )
+        repeat_bounded(&self.parser, 0, None, input)
     }
 }
 
@@ -355,19 +1235,174 @@ impl<I, T, P> Parser<I, Vec<T>> for Many1<P>
 where
     I: Input,
     P: Parser<I, T>,
-    T: Clone,
 {
     fn parse(&self, input: I) -> ParseResult<I, Vec<T>> {
-        fold_many1(
-            &self.parser,
-            Vec::new(),
-            |mut acc, item| {
-                acc.push(item);
-                acc
-            },
+        repeat_bounded(&self.parser, 1, None, input)
+    }
+}
+
+/// Bounds accepted by `Parser::repeat`: an exact count or any range of counts
+pub trait RepeatRange {
+    /// The minimum count and the maximum count, if there is one
+    fn bounds(&self) -> (usize, Option<usize>);
+}
+
+impl RepeatRange for usize {
+    fn bounds(&self) -> (usize, Option<usize>) {
+        (*self, Some(*self))
+    }
+}
+
+impl RepeatRange for Range<usize> {
+    fn bounds(&self) -> (usize, Option<usize>) {
+        (self.start, Some(self.end.saturating_sub(1)))
+    }
+}
+
+impl RepeatRange for RangeInclusive<usize> {
+    fn bounds(&self) -> (usize, Option<usize>) {
+        (*self.start(), Some(*self.end()))
+    }
+}
+
+impl RepeatRange for RangeFrom<usize> {
+    fn bounds(&self) -> (usize, Option<usize>) {
+        (self.start, None)
+    }
+}
+
+impl RepeatRange for RangeTo<usize> {
+    fn bounds(&self) -> (usize, Option<usize>) {
+        (0, Some(self.end.saturating_sub(1)))
+    }
+}
+
+impl RepeatRange for RangeToInclusive<usize> {
+    fn bounds(&self) -> (usize, Option<usize>) {
+        (0, Some(self.end))
+    }
+}
+
+impl RepeatRange for RangeFull {
+    fn bounds(&self) -> (usize, Option<usize>) {
+        (0, None)
+    }
+}
+
+/// Repeat combinator - a bounded number of occurrences
+pub struct Repeat<P> {
+    parser: P,
+    min: usize,
+    max: Option<usize>,
+}
+
+impl<I, T, P> Parser<I, Vec<T>> for Repeat<P>
+where
+    I: Input,
+    P: Parser<I, T>,
+{
+    fn parse(&self, input: I) -> ParseResult<I, Vec<T>> {
+        repeat_bounded(&self.parser, self.min, self.max, input)
+    }
+}
+
+/// Collect between `min` and `max` repetitions, shared by `Many`, `Many1` and `Repeat`
+pub(crate) fn repeat_bounded<I, T, P>(
+    parser: &P,
+    min: usize,
+    max: Option<usize>,
+    input: I,
+) -> ParseResult<I, Vec<T>>
+where
+    I: Input,
+    P: Parser<I, T>,
+{
+    let push = |mut items: Vec<T>, item| {
+        items.push(item);
+        items
+    };
+    fold_bounded(parser, min, max, Vec::new(), push, input)
+}
+
+/// Generic fold over between `min` and `max` parser repetitions
+fn fold_bounded<I, T, P, A, F>(
+    parser: &P,
+    min: usize,
+    max: Option<usize>,
+    init: A,
+    mut f: F,
+    mut input: I,
+) -> ParseResult<I, A>
+where
+    I: Input,
+    P: Parser<I, T>,
+    F: FnMut(A, T) -> A,
+{
+    let mut acc = init;
+    let mut count = 0;
+    while max.is_none_or(|max| count < max) {
+        let mark = Mark::new(&input);
+        match parser.parse_or_restore(input) {
+            // A zero-width success would repeat forever, so once `min` is met it ends
+            // the repetition
+            Ok((_, remaining)) if count >= min && !mark.progressed_to(&remaining) => {
+                input = remaining;
+                break;
+            }
+            Ok((item, remaining)) => {
+                acc = f(acc, item);
+                count += 1;
+                input = remaining;
+            }
+            Err((err, _)) if count < min => return Err(err),
+            Err((_, restored)) => {
+                input = restored;
+                break;
+            }
+        }
+    }
+    // Only reachable for an empty range such as `3..3`
+    if count < min {
+        return Err(ParseError::message(
+            format!("expected at least {} items, found {}", min, count),
             input,
-        )
+        ));
     }
+    Ok((acc, input))
+}
+
+// Entry points
+
+/// Run a parser over the whole input, returning just the parsed value
+///
+/// Fails with an "unexpected trailing input" error if the parser does not consume
+/// everything. Since the input is known to be complete, any `Incomplete` error is
+/// reported as `UnexpectedEof`.
+pub fn parse_complete<I, T, P>(parser: &P, input: I) -> Result<T, ParseError<I>>
+where
+    I: Input,
+    P: Parser<I, T>,
+{
+    let (value, remaining) = parser.parse(input).map_err(ParseError::into_complete)?;
+    if remaining.is_empty() {
+        Ok(value)
+    } else {
+        Err(ParseError::message("unexpected trailing input", remaining))
+    }
+}
+
+/// Run a parser, printing a report of the furthest error to stderr if it fails
+///
+/// Unlike `trace`, which logs every attempt including those undone by
+/// backtracking, this prints once, after the whole parse has failed. The report
+/// format is described on `furthest_error_report`.
+pub fn parse_or_report<'a, T, P>(parser: &P, input: &'a str) -> ParseResult<&'a str, T>
+where
+    P: Parser<&'a str, T>,
+{
+    parser
+        .parse(input)
+        .inspect_err(|err| eprintln!("{}", furthest_error_report(err, input)))
 }
 
 // Pure/Return functions for Applicative
@@ -450,6 +1485,91 @@ where
     }
 }
 
+/// FoldManyMN combinator - fold over between `min` and `max` occurrences
+pub struct FoldManyMN<P, A, F, T> {
+    parser: P,
+    min: usize,
+    max: usize,
+    init: A,
+    f: F,
+    _phantom: PhantomData<T>,
+}
+
+impl<I, T, A, P, F> Parser<I, A> for FoldManyMN<P, A, F, T>
+where
+    I: Input,
+    P: Parser<I, T>,
+    A: Clone,
+    F: FnMut(A, T) -> A + Clone,
+{
+    fn parse(&self, input: I) -> ParseResult<I, A> {
+        fold_bounded(
+            &self.parser,
+            self.min,
+            Some(self.max),
+            self.init.clone(),
+            self.f.clone(),
+            input,
+        )
+    }
+}
+
+/// Apply combinator - calls a function with the flattened results of an `and` chain
+pub struct Apply<P, F, T, Flat> {
+    parser: P,
+    f: F,
+    _phantom: PhantomData<(T, Flat)>,
+}
+
+impl<I, T, Flat, R, P, F> Parser<I, R> for Apply<P, F, T, Flat>
+where
+    I: Input,
+    P: Parser<I, T>,
+    T: NestedTuple<Flat>,
+    F: ApplyFn<Flat, R>,
+{
+    fn parse(&self, input: I) -> ParseResult<I, R> {
+        self.parser
+            .parse(input)
+            .map(|(result, remaining)| (self.f.apply(result.flatten()), remaining))
+    }
+}
+
+/// A left-nested tuple such as `((a, b), c)`, as produced by chaining `and`
+/// Implemented for 2 to 5 elements, flattening into `(a, b, c)`.
+pub trait NestedTuple<Flat> {
+    fn flatten(self) -> Flat;
+}
+
+/// A function taking the elements of the tuple `Args` as separate arguments
+pub trait ApplyFn<Args, R> {
+    fn apply(&self, args: Args) -> R;
+}
+
+macro_rules! impl_apply {
+    ($nested:tt => $($arg:ident),+) => {
+        impl<$($arg),+> NestedTuple<($($arg,)+)> for $nested {
+            #[allow(non_snake_case)]
+            fn flatten(self) -> ($($arg,)+) {
+                let $nested = self;
+                ($($arg,)+)
+            }
+        }
+
+        impl<$($arg,)+ R, F: Fn($($arg),+) -> R> ApplyFn<($($arg,)+), R> for F {
+            #[allow(non_snake_case)]
+            fn apply(&self, ($($arg,)+): ($($arg,)+)) -> R {
+                self($($arg),+)
+            }
+        }
+    };
+}
+
+impl_apply!((A, B) => A, B);
+impl_apply!(((A, B), C) => A, B, C);
+impl_apply!((((A, B), C), D) => A, B, C, D);
+impl_apply!(((((A, B), C), D), E) => A, B, C, D, E);
+
 // Higher-order functional combinators
 
 /// Apply a binary function to two parser results
@@ -460,7 +1580,7 @@ where
     P2: Parser<I, T2>,
     F: Fn(T1, T2) -> R,
 {
-    p1.and(p2).map(move |(a, b)| f(a, b))
+    p1.and(p2).apply(f)
 }
 
 /// Apply a ternary function to three parser results
@@ -472,7 +1592,7 @@ where
     P3: Parser<I, T3>,
     F: Fn(T1, T2, T3) -> R,
 {
-    p1.and(p2).and(p3).map(move |((a, b), c)| f(a, b, c))
+    p1.and(p2).and(p3).apply(f)
 }
 
 /// Parse two parsers and return their results as a tuple
@@ -495,3 +1615,63 @@ where
 {
     p1.and(p2).and(p3).map(|((a, b), c)| (a, b, c))
 }
+
+/// Run a tuple of parsers in sequence, returning a tuple of their results
+/// Stops at the first failure. Generalizes tuple2/tuple3 to tuples of up to 12.
+pub fn tuple<I: Input, T, Ps: ParserTuple<I, T>>(parsers: Ps) -> Tuple<I, T, Ps> {
+    Tuple {
+        parsers,
+        _phantom: PhantomData,
+    }
+}
+
+pub struct Tuple<I, T, Ps> {
+    parsers: Ps,
+    _phantom: PhantomData<(I, T)>,
+}
+
+impl<I, T, Ps> Parser<I, T> for Tuple<I, T, Ps>
+where
+    I: Input,
+    Ps: ParserTuple<I, T>,
+{
+    fn parse(&self, input: I) -> ParseResult<I, T> {
+        self.parsers.parse_tuple(input)
+    }
+}
+
+/// A tuple of parsers that can be run in sequence by `tuple()`
+/// Implemented for tuples of 1 to 12 parsers, producing a tuple of their results.
+pub trait ParserTuple<I: Input, T> {
+    fn parse_tuple(&self, input: I) -> ParseResult<I, T>;
+}
+
+macro_rules! impl_parser_tuple {
+    ($($parser:ident $output:ident $index:tt),+) => {
+        impl<I: Input, $($output,)+ $($parser: Parser<I, $output>),+> ParserTuple<I, ($($output,)+)>
+            for ($($parser,)+)
+        {
+            #[allow(non_snake_case)]
+            fn parse_tuple(&self, input: I) -> ParseResult<I, ($($output,)+)> {
+                let remaining = input;
+                $(
+                    let ($output, remaining) = self.$index.parse(remaining)?;
+                )+
+                Ok((($($output,)+), remaining))
+            }
+        }
+    };
+}
+
+impl_parser_tuple!(P0 T0 0);
+impl_parser_tuple!(P0 T0 0, P1 T1 1);
+impl_parser_tuple!(P0 T0 0, P1 T1 1, P2 T2 2);
+impl_parser_tuple!(P0 T0 0, P1 T1 1, P2 T2 2, P3 T3 3);
+impl_parser_tuple!(P0 T0 0, P1 T1 1, P2 T2 2, P3 T3 3, P4 T4 4);
+impl_parser_tuple!(P0 T0 0, P1 T1 1, P2 T2 2, P3 T3 3, P4 T4 4, P5 T5 5);
+impl_parser_tuple!(P0 T0 0, P1 T1 1, P2 T2 2, P3 T3 3, P4 T4 4, P5 T5 5, P6 T6 6);
+impl_parser_tuple!(P0 T0 0, P1 T1 1, P2 T2 2, P3 T3 3, P4 T4 4, P5 T5 5, P6 T6 6, P7 T7 7);
+impl_parser_tuple!(P0 T0 0, P1 T1 1, P2 T2 2, P3 T3 3, P4 T4 4, P5 T5 5, P6 T6 6, P7 T7 7, P8 T8 8);
+impl_parser_tuple!(P0 T0 0, P1 T1 1, P2 T2 2, P3 T3 3, P4 T4 4, P5 T5 5, P6 T6 6, P7 T7 7, P8 T8 8, P9 T9 9);
+impl_parser_tuple!(P0 T0 0, P1 T1 1, P2 T2 2, P3 T3 3, P4 T4 4, P5 T5 5, P6 T6 6, P7 T7 7, P8 T8 8, P9 T9 9, P10 T10 10);
+impl_parser_tuple!(P0 T0 0, P1 T1 1, P2 T2 2, P3 T3 3, P4 T4 4, P5 T5 5, P6 T6 6, P7 T7 7, P8 T8 8, P9 T9 9, P10 T10 10, P11 T11 11);
@@ -0,0 +1,200 @@
+//! Error recovery: keep parsing after a failure and collect every error.
+//!
+//! Recovering parsers report their errors into a shared `Diagnostics` collector
+//! instead of aborting, which lets editors and linters see all syntax errors in
+//! one pass.
+
+use crate::input::made_progress;
+use crate::{Input, ParseError, ParseResult, Parser};
+use std::cell::RefCell;
+use std::marker::PhantomData;
+use std::rc::Rc;
+
+/// A shared collector for errors recovered from during a parse
+///
+/// Clones share the same underlying list, so the collector can be captured by
+/// several recovering parsers and inspected once parsing is done.
+#[derive(Debug)]
+pub struct Diagnostics<I> {
+    errors: Rc<RefCell<Vec<ParseError<I>>>>,
+}
+
+impl<I> Diagnostics<I> {
+    /// Create an empty collector
+    pub fn new() -> Self {
+        Diagnostics {
+            errors: Rc::new(RefCell::new(Vec::new())),
+        }
+    }
+
+    /// Record an error
+    pub fn push(&self, error: ParseError<I>) {
+        self.errors.borrow_mut().push(error);
+    }
+
+    /// Number of errors recorded so far
+    pub fn len(&self) -> usize {
+        self.errors.borrow().len()
+    }
+
+    /// Returns true if no errors have been recorded
+    pub fn is_empty(&self) -> bool {
+        self.errors.borrow().is_empty()
+    }
+
+    /// Remove and return every recorded error
+    pub fn take(&self) -> Vec<ParseError<I>> {
+        std::mem::take(&mut self.errors.borrow_mut())
+    }
+}
+
+impl<I> Clone for Diagnostics<I> {
+    fn clone(&self) -> Self {
+        Diagnostics {
+            errors: Rc::clone(&self.errors),
+        }
+    }
+}
+
+impl<I> Default for Diagnostics<I> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Run `parser`, and on failure record its error and skip ahead past the next
+/// match of `sync`
+///
+/// Produces `Some(result)` on success and `None` after recovering. Input is
+/// skipped one item at a time until `sync` matches past the point of failure;
+/// the `sync` match itself is consumed, so e.g.
+/// `recover(statement, char(';'), &diagnostics).many()` resumes at the next
+/// statement. If `sync` never matches, the rest of the input is skipped. A
+/// failure on already empty input is returned as an error instead, so
+/// repetitions of a recovering parser still terminate.
+pub fn recover<I, T, U, P, S>(
+    parser: P,
+    sync: S,
+    diagnostics: &Diagnostics<I>,
+) -> Recover<P, S, I, U>
+where
+    I: Input,
+    P: Parser<I, T>,
+    S: Parser<I, U>,
+{
+    Recover {
+        parser,
+        sync,
+        diagnostics: diagnostics.clone(),
+        _phantom: PhantomData,
+    }
+}
+
+pub struct Recover<P, S, I, U> {
+    parser: P,
+    sync: S,
+    diagnostics: Diagnostics<I>,
+    _phantom: PhantomData<U>,
+}
+
+impl<I, T, U, P, S> Parser<I, Option<T>> for Recover<P, S, I, U>
+where
+    I: Input,
+    P: Parser<I, T>,
+    S: Parser<I, U>,
+{
+    fn parse(&self, input: I) -> ParseResult<I, Option<T>> {
+        let error = match self.parser.parse(input.clone()) {
+            Ok((result, remaining)) => return Ok((Some(result), remaining)),
+            Err(error) if input.is_empty() => return Err(error),
+            Err(error) => error,
+        };
+        self.diagnostics.push(error);
+        Ok((None, skip_past(&self.sync, input)))
+    }
+}
+
+/// Run `parser`, and on failure skip ahead past the next match of `sync` and
+/// yield `default` in its place
+///
+/// The silent counterpart to `recover`: nothing is recorded, and the result is
+/// a placeholder rather than an `Option`. Unlike `or_default`, the bad region is
+/// consumed, so a surrounding repetition carries on with the next item. Skipping
+/// and the handling of empty input work as in `recover`.
+pub fn recover_with<I, T, U, P, S>(parser: P, default: T, sync: S) -> RecoverWith<P, S, T, U>
+where
+    I: Input,
+    T: Clone,
+    P: Parser<I, T>,
+    S: Parser<I, U>,
+{
+    RecoverWith {
+        parser,
+        default,
+        sync,
+        _phantom: PhantomData,
+    }
+}
+
+pub struct RecoverWith<P, S, T, U> {
+    parser: P,
+    default: T,
+    sync: S,
+    _phantom: PhantomData<U>,
+}
+
+impl<I, T, U, P, S> Parser<I, T> for RecoverWith<P, S, T, U>
+where
+    I: Input,
+    T: Clone,
+    P: Parser<I, T>,
+    S: Parser<I, U>,
+{
+    fn parse(&self, input: I) -> ParseResult<I, T> {
+        match self.parser.parse(input.clone()) {
+            Ok(result) => Ok(result),
+            Err(error) if input.is_empty() => Err(error),
+            Err(_) => Ok((self.default.clone(), skip_past(&self.sync, input))),
+        }
+    }
+}
+
+/// Skip input one item at a time until `sync` matches, returning the input after
+/// the match, or the empty remainder if it never does
+///
+/// A match that ends where the failure happened would leave nothing consumed, and
+/// a surrounding repetition would stop there, so such a match is skipped over.
+fn skip_past<I, U, S>(sync: &S, start: I) -> I
+where
+    I: Input,
+    S: Parser<I, U>,
+{
+    let mut input = start.clone();
+    loop {
+        if let Ok((_, after_sync)) = sync.parse(input.clone())
+            && made_progress(&start, &after_sync)
+        {
+            return after_sync;
+        }
+        match input.uncons() {
+            Some((_, rest)) => input = rest,
+            None => return input,
+        }
+    }
+}
+
+/// Run a parser built around a fresh `Diagnostics` collector, returning the
+/// parse result together with every error that was recovered from
+pub fn parse_with_diagnostics<I, T, P, F>(
+    build: F,
+    input: I,
+) -> (ParseResult<I, T>, Vec<ParseError<I>>)
+where
+    I: Input,
+    P: Parser<I, T>,
+    F: FnOnce(&Diagnostics<I>) -> P,
+{
+    let diagnostics = Diagnostics::new();
+    let result = build(&diagnostics).parse(input);
+    (result, diagnostics.take())
+}
@@ -0,0 +1,243 @@
+//! Parsers that thread mutable user state through a parse.
+//!
+//! Some grammars need context that the input alone does not carry, such as the
+//! current indentation stack or the set of names declared so far. A
+//! `StatefulParser` receives `&mut S` alongside its input. The trait is separate
+//! from `Parser`, so plain parsers are unaffected; `lift` embeds one in a stateful
+//! grammar. The module is not glob re-exported, so use it by path.
+
+use crate::input::made_progress;
+use crate::{Input, ParseError, ParseResult, Parser};
+use std::marker::PhantomData;
+
+/// A parser with access to mutable state of type `S`
+pub trait StatefulParser<S, I: Input, T> {
+    /// Parse the input, reading and updating `state` along the way
+    fn parse_with(&self, state: &mut S, input: I) -> ParseResult<I, T>;
+
+    /// Transform the result of a successful parse
+    fn map<U, F>(self, f: F) -> Map<Self, F, T>
+    where
+        Self: Sized,
+        F: Fn(T) -> U,
+    {
+        Map {
+            parser: self,
+            f,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Parse this, then `other`, keeping both results
+    fn and<U, P>(self, other: P) -> And<Self, P>
+    where
+        Self: Sized,
+        P: StatefulParser<S, I, U>,
+    {
+        And {
+            left: self,
+            right: other,
+        }
+    }
+
+    /// Try this parser, and if it fails try `other`
+    ///
+    /// The state is not rolled back, so a failing left side should only update
+    /// the state once it can no longer fail.
+    fn or<P>(self, other: P) -> Or<Self, P>
+    where
+        Self: Sized,
+        P: StatefulParser<S, I, T>,
+    {
+        Or {
+            left: self,
+            right: other,
+        }
+    }
+
+    /// Parse zero or more occurrences, stopping at the first failure
+    fn many(self) -> Many<Self>
+    where
+        Self: Sized,
+    {
+        Many { parser: self }
+    }
+
+    /// Combine the result with the state, failing with the `Err` message at the
+    /// position where this parser started
+    fn with_state<U, F>(self, f: F) -> WithState<Self, F, T>
+    where
+        Self: Sized,
+        F: Fn(&mut S, T) -> Result<U, String>,
+    {
+        WithState {
+            parser: self,
+            f,
+            _phantom: PhantomData,
+        }
+    }
+}
+
+/// Run a stateful parser from an initial state, returning the final state as well
+pub fn run<S, I, T, P>(parser: &P, mut state: S, input: I) -> (ParseResult<I, T>, S)
+where
+    I: Input,
+    P: StatefulParser<S, I, T>,
+{
+    let result = parser.parse_with(&mut state, input);
+    (result, state)
+}
+
+/// Build a stateful parser from a function
+pub fn stateful<S, I, T, F>(f: F) -> FromFn<F>
+where
+    I: Input,
+    F: Fn(&mut S, I) -> ParseResult<I, T>,
+{
+    FromFn { f }
+}
+
+pub struct FromFn<F> {
+    f: F,
+}
+
+impl<S, I, T, F> StatefulParser<S, I, T> for FromFn<F>
+where
+    I: Input,
+    F: Fn(&mut S, I) -> ParseResult<I, T>,
+{
+    fn parse_with(&self, state: &mut S, input: I) -> ParseResult<I, T> {
+        (self.f)(state, input)
+    }
+}
+
+/// Use a plain parser inside a stateful grammar, leaving the state untouched
+pub fn lift<S, P>(parser: P) -> Lift<S, P> {
+    Lift {
+        parser,
+        _phantom: PhantomData,
+    }
+}
+
+pub struct Lift<S, P> {
+    parser: P,
+    _phantom: PhantomData<S>,
+}
+
+impl<S, I, T, P> StatefulParser<S, I, T> for Lift<S, P>
+where
+    I: Input,
+    P: Parser<I, T>,
+{
+    fn parse_with(&self, _state: &mut S, input: I) -> ParseResult<I, T> {
+        self.parser.parse(input)
+    }
+}
+
+/// Map combinator - transforms the result of a stateful parser
+pub struct Map<P, F, T> {
+    parser: P,
+    f: F,
+    _phantom: PhantomData<T>,
+}
+
+impl<S, I, T, U, P, F> StatefulParser<S, I, U> for Map<P, F, T>
+where
+    I: Input,
+    P: StatefulParser<S, I, T>,
+    F: Fn(T) -> U,
+{
+    fn parse_with(&self, state: &mut S, input: I) -> ParseResult<I, U> {
+        self.parser
+            .parse_with(state, input)
+            .map(|(result, remaining)| ((self.f)(result), remaining))
+    }
+}
+
+/// And combinator - parses two stateful parsers in sequence
+pub struct And<L, R> {
+    left: L,
+    right: R,
+}
+
+impl<S, I, T, U, L, R> StatefulParser<S, I, (T, U)> for And<L, R>
+where
+    I: Input,
+    L: StatefulParser<S, I, T>,
+    R: StatefulParser<S, I, U>,
+{
+    fn parse_with(&self, state: &mut S, input: I) -> ParseResult<I, (T, U)> {
+        let (left_result, input1) = self.left.parse_with(state, input)?;
+        let (right_result, input2) = self.right.parse_with(state, input1)?;
+        Ok(((left_result, right_result), input2))
+    }
+}
+
+/// Or combinator - tries the left stateful parser, then the right
+pub struct Or<L, R> {
+    left: L,
+    right: R,
+}
+
+impl<S, I, T, L, R> StatefulParser<S, I, T> for Or<L, R>
+where
+    I: Input,
+    L: StatefulParser<S, I, T>,
+    R: StatefulParser<S, I, T>,
+{
+    fn parse_with(&self, state: &mut S, input: I) -> ParseResult<I, T> {
+        match self.left.parse_with(state, input.clone()) {
+            Ok(result) => Ok(result),
+            Err(left_err) => match self.right.parse_with(state, input) {
+                Ok(result) => Ok(result),
+                Err(right_err) => Err(ParseError::many(vec![left_err, right_err])),
+            },
+        }
+    }
+}
+
+/// Many combinator - zero or more occurrences of a stateful parser
+pub struct Many<P> {
+    parser: P,
+}
+
+impl<S, I, T, P> StatefulParser<S, I, Vec<T>> for Many<P>
+where
+    I: Input,
+    P: StatefulParser<S, I, T>,
+{
+    fn parse_with(&self, state: &mut S, mut input: I) -> ParseResult<I, Vec<T>> {
+        let mut results = Vec::new();
+        while let Ok((result, remaining)) = self.parser.parse_with(state, input.clone()) {
+            // A zero-width success would repeat forever, so it ends the repetition
+            if !made_progress(&input, &remaining) {
+                break;
+            }
+            results.push(result);
+            input = remaining;
+        }
+        Ok((results, input))
+    }
+}
+
+/// WithState combinator - combines a result with the state
+pub struct WithState<P, F, T> {
+    parser: P,
+    f: F,
+    _phantom: PhantomData<T>,
+}
+
+impl<S, I, T, U, P, F> StatefulParser<S, I, U> for WithState<P, F, T>
+where
+    I: Input,
+    P: StatefulParser<S, I, T>,
+    F: Fn(&mut S, T) -> Result<U, String>,
+{
+    fn parse_with(&self, state: &mut S, input: I) -> ParseResult<I, U> {
+        let (result, remaining) = self.parser.parse_with(state, input.clone())?;
+        match (self.f)(state, result) {
+            Ok(value) => Ok((value, remaining)),
+            Err(message) => Err(ParseError::message(message, input)),
+        }
+    }
+}
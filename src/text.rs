@@ -1,6 +1,8 @@
 //! Text and string parsing utilities.
 
-use crate::{Input, ParseError, ParseResult, Parser, combinators::*};
+use crate::{IgnoreWs, Input, ParseError, ParseResult, Parser, combinators::*};
+use std::cell::RefCell;
+use std::collections::HashSet;
 
 /// Parse a specific character
 /// Composed using the token combinator
@@ -38,11 +40,8 @@ impl<'a> Parser<&'a str, String> for String_ {
                     ));
                 }
                 None => {
-                    return Err(ParseError::expected(
-                        format!("string '{}'", self.expected),
-                        Some("end of input"),
-                        original_input,
-                    ));
+                    let needed = self.expected.chars().count() - matched.chars().count();
+                    return Err(ParseError::incomplete(Some(needed)));
                 }
             }
         }
@@ -51,28 +50,137 @@ impl<'a> Parser<&'a str, String> for String_ {
     }
 }
 
+/// Whether a character can continue a word, for keyword boundary checks
+fn is_word_char(c: &char) -> bool {
+    c.is_alphanumeric() || *c == '_'
+}
+
+/// Parse a keyword, only matching when it is not immediately followed by an
+/// alphanumeric or underscore character
+///
+/// Unlike `string("true")`, `keyword("true")` rejects the prefix of `"truelove"`.
+/// The boundary is checked with a negative lookahead, so a failure never consumes
+/// input. Returns a slice of the input.
+pub fn keyword(s: &str) -> Keyword {
+    Keyword {
+        expected: s.to_string(),
+    }
+}
+
+pub struct Keyword {
+    expected: String,
+}
+
+impl<'a> Parser<&'a str, &'a str> for Keyword {
+    fn parse(&self, input: &'a str) -> ParseResult<&'a str, &'a str> {
+        let found = match input.strip_prefix(self.expected.as_str()) {
+            Some(remaining) => match remaining.chars().next() {
+                Some(c) if is_word_char(&c) => format!("character '{}'", c),
+                _ => return Ok(input.split_at(self.expected.len())),
+            },
+            None => match input
+                .chars()
+                .zip(self.expected.chars())
+                .find(|(actual, expected)| actual != expected)
+            {
+                Some((c, _)) => format!("character '{}'", c),
+                None => {
+                    let needed = self.expected.chars().count() - input.chars().count();
+                    return Err(ParseError::incomplete(Some(needed)));
+                }
+            },
+        };
+        Err(ParseError::expected(
+            format!("keyword '{}'", self.expected),
+            Some(found),
+            input,
+        ))
+    }
+}
+
+/// Parse any of a set of keywords, returning the value paired with the one found
+///
+/// Keywords respect word boundaries like `keyword`, and the longest matching keyword
+/// wins regardless of order, so `"in"` and `"int"` can share a set. Usually built
+/// with the `keywords!` macro.
+pub fn keyword_map<T: Clone>(entries: Vec<(&str, T)>) -> KeywordMap<T> {
+    let mut entries: Vec<(String, T)> = entries
+        .into_iter()
+        .map(|(keyword, value)| (keyword.to_string(), value))
+        .collect();
+    entries.sort_by_key(|(keyword, _)| std::cmp::Reverse(keyword.len()));
+    KeywordMap { entries }
+}
+
+pub struct KeywordMap<T> {
+    entries: Vec<(String, T)>,
+}
+
+impl<'a, T: Clone> Parser<&'a str, T> for KeywordMap<T> {
+    fn parse(&self, input: &'a str) -> ParseResult<&'a str, T> {
+        for (keyword, value) in &self.entries {
+            if let Some(remaining) = input.strip_prefix(keyword.as_str())
+                && !remaining.chars().next().is_some_and(|c| is_word_char(&c))
+            {
+                return Ok((value.clone(), remaining));
+            }
+        }
+        let Some(c) = input.chars().next() else {
+            return Err(ParseError::incomplete(None));
+        };
+        if self
+            .entries
+            .iter()
+            .any(|(keyword, _)| keyword.len() > input.len() && keyword.starts_with(input))
+        {
+            return Err(ParseError::incomplete(None));
+        }
+        let keywords: Vec<_> = self
+            .entries
+            .iter()
+            .map(|(keyword, _)| format!("'{}'", keyword))
+            .collect();
+        Err(ParseError::expected(
+            format!("one of keywords {}", keywords.join(", ")),
+            Some(format!("character '{}'", c)),
+            input,
+        ))
+    }
+}
+
+/// Build a `keyword_map` parser from `"keyword" => value` pairs
+///
+/// E.g. `keywords! { "add" => Op::Add, "sub" => Op::Sub }` replaces a chain of
+/// `keyword("add").map(|_| Op::Add).or(...)`.
+#[macro_export]
+macro_rules! keywords {
+    ($($keyword:expr => $value:expr),+ $(,)?) => {
+        $crate::text::keyword_map(vec![$(($keyword, $value)),+])
+    };
+}
+
 /// Parse any alphabetic character
-/// Composed using the satisfy combinator
+/// Composed using the satisfy_labeled combinator
 pub fn alpha<'a>() -> impl Parser<&'a str, char> {
-    satisfy(|c: &char| c.is_alphabetic())
+    satisfy_labeled("alphabetic character", |c: &char| c.is_alphabetic())
 }
 
 /// Parse any numeric digit
-/// Composed using the satisfy combinator
+/// Composed using the satisfy_labeled combinator
 pub fn digit<'a>() -> impl Parser<&'a str, char> {
-    satisfy(|c: &char| c.is_ascii_digit())
+    satisfy_labeled("digit", |c: &char| c.is_ascii_digit())
 }
 
 /// Parse any alphanumeric character
-/// Composed using the satisfy combinator
+/// Composed using the satisfy_labeled combinator
 pub fn alphanumeric<'a>() -> impl Parser<&'a str, char> {
-    satisfy(|c: &char| c.is_alphanumeric())
+    satisfy_labeled("alphanumeric character", |c: &char| c.is_alphanumeric())
 }
 
 /// Parse any whitespace character
-/// Composed using the satisfy combinator
+/// Composed using the satisfy_labeled combinator
 pub fn space<'a>() -> impl Parser<&'a str, char> {
-    satisfy(|c: &char| c.is_whitespace())
+    satisfy_labeled("whitespace", |c: &char| c.is_whitespace())
 }
 
 /// Convert a vector of characters to a String
@@ -80,16 +188,307 @@ pub fn chars_to_string(chars: Vec<char>) -> String {
     chars.into_iter().collect()
 }
 
+/// Split off the longest prefix whose characters all satisfy the predicate
+fn split_while<'a, F>(input: &'a str, predicate: &F) -> (&'a str, &'a str)
+where
+    F: Fn(&char) -> bool,
+{
+    let end = input
+        .char_indices()
+        .find(|(_, c)| !predicate(c))
+        .map_or(input.len(), |(i, _)| i);
+    input.split_at(end)
+}
+
+/// Parse zero or more characters satisfying the predicate
+/// Returns a slice of the input, so no allocation is performed
+pub fn take_while<F>(predicate: F) -> TakeWhile<F>
+where
+    F: Fn(&char) -> bool,
+{
+    TakeWhile { predicate }
+}
+
+pub struct TakeWhile<F> {
+    predicate: F,
+}
+
+impl<'a, F> Parser<&'a str, &'a str> for TakeWhile<F>
+where
+    F: Fn(&char) -> bool,
+{
+    fn parse(&self, input: &'a str) -> ParseResult<&'a str, &'a str> {
+        Ok(split_while(input, &self.predicate))
+    }
+}
+
+/// Parse one or more characters satisfying the predicate
+/// Returns a slice of the input, so no allocation is performed
+pub fn take_while1<F>(predicate: F) -> TakeWhile1<F>
+where
+    F: Fn(&char) -> bool,
+{
+    TakeWhile1 {
+        predicate,
+        expected: "item satisfying predicate",
+    }
+}
+
+pub struct TakeWhile1<F> {
+    predicate: F,
+    expected: &'static str,
+}
+
+impl<'a, F> Parser<&'a str, &'a str> for TakeWhile1<F>
+where
+    F: Fn(&char) -> bool,
+{
+    fn parse(&self, input: &'a str) -> ParseResult<&'a str, &'a str> {
+        match split_while(input, &self.predicate) {
+            ("", _) if input.is_empty() => Err(ParseError::incomplete(Some(1))),
+            ("", rest) => Err(ParseError::expected(
+                self.expected,
+                rest.chars().next().map(|c| format!("{:?}", c)),
+                input,
+            )),
+            result => Ok(result),
+        }
+    }
+}
+
+/// Append zero or more characters satisfying the predicate to a caller-owned buffer
+/// Returns how many characters were appended; the buffer is never cleared, so one
+/// `String` can be cleared and reused across calls instead of allocating per token
+pub fn fill_while<F>(predicate: F, buffer: &RefCell<String>) -> FillWhile<'_, F>
+where
+    F: Fn(&char) -> bool,
+{
+    FillWhile { predicate, buffer }
+}
+
+pub struct FillWhile<'b, F> {
+    predicate: F,
+    buffer: &'b RefCell<String>,
+}
+
+impl<I, F> Parser<I, usize> for FillWhile<'_, F>
+where
+    I: Input<Item = char>,
+    F: Fn(&char) -> bool,
+{
+    fn parse(&self, mut input: I) -> ParseResult<I, usize> {
+        let mut count = 0;
+        while let Some((c, rest)) = input.uncons() {
+            if !(self.predicate)(&c) {
+                break;
+            }
+            // Borrowed only for the push, so the predicate may read the buffer
+            self.buffer.borrow_mut().push(c);
+            count += 1;
+            input = rest;
+        }
+        Ok((count, input))
+    }
+}
+
+/// Parse an identifier whose first character satisfies `start` and whose
+/// remaining characters satisfy `continue_`
+/// Returns a slice of the input, so no allocation is performed
+pub fn identifier_with<S, C>(start: S, continue_: C) -> IdentifierWith<S, C>
+where
+    S: Fn(&char) -> bool,
+    C: Fn(&char) -> bool,
+{
+    IdentifierWith { start, continue_ }
+}
+
+pub struct IdentifierWith<S, C> {
+    start: S,
+    continue_: C,
+}
+
+impl<'a, S, C> Parser<&'a str, &'a str> for IdentifierWith<S, C>
+where
+    S: Fn(&char) -> bool,
+    C: Fn(&char) -> bool,
+{
+    fn parse(&self, input: &'a str) -> ParseResult<&'a str, &'a str> {
+        match input.uncons() {
+            Some((c, remaining)) if (self.start)(&c) => {
+                let (tail, remaining) = split_while(remaining, &self.continue_);
+                let end = c.len_utf8() + tail.len();
+                Ok((&input[..end], remaining))
+            }
+            Some((c, _)) => Err(ParseError::expected(
+                "identifier",
+                Some(format!("character '{}'", c)),
+                input,
+            )),
+            None => Err(ParseError::incomplete(Some(1))),
+        }
+    }
+}
+
+/// Parse an identifier: a letter or underscore, followed by any number of
+/// letters, digits, or underscores
+/// Composed using identifier_with()
+pub fn identifier<'a>() -> impl Parser<&'a str, &'a str> {
+    identifier_with(
+        |c: &char| c.is_alphabetic() || *c == '_',
+        |c: &char| c.is_alphanumeric() || *c == '_',
+    )
+}
+
 /// Parse zero or more whitespace characters
-/// Composed using space(), many(), and map()
-pub fn spaces<'a>() -> impl Parser<&'a str, String> {
-    space().many().map(chars_to_string)
+/// Composed using take_while(), so the result borrows from the input
+pub fn spaces<'a>() -> impl Parser<&'a str, &'a str> {
+    take_while(|c: &char| c.is_whitespace())
 }
 
 /// Parse one or more whitespace characters
-/// Composed using space(), many1(), and map()
-pub fn spaces1<'a>() -> impl Parser<&'a str, String> {
-    space().many1().map(chars_to_string)
+/// Built on TakeWhile1, so the result borrows from the input
+pub fn spaces1<'a>() -> impl Parser<&'a str, &'a str> {
+    TakeWhile1 {
+        predicate: |c: &char| c.is_whitespace(),
+        expected: "whitespace",
+    }
+}
+
+/// Run a parser, then skip any trailing whitespace
+/// Composed using skip() and spaces()
+///
+/// The usual convention is for every token to consume only the whitespace that
+/// follows it, and for the top-level parser to skip leading whitespace once
+/// (e.g. `spaces().and(lexeme(...))`), so no gap is ever handled twice.
+pub fn lexeme<'a, P, T>(parser: P) -> impl Parser<&'a str, T>
+where
+    P: Parser<&'a str, T>,
+{
+    parser.skip(spaces())
+}
+
+/// Skip any mix of whitespace, line comments and block comments
+///
+/// For example `skip_ws_and_comments("//", ("/*", "*/"))` handles C-style
+/// comments. An empty marker disables that kind of comment. Block comments do not
+/// nest unless enabled with `.nested()`. An unterminated block comment is
+/// `Incomplete`, since more input could still close it.
+pub fn skip_ws_and_comments(line_comment: &str, block_comment: (&str, &str)) -> SkipWsAndComments {
+    SkipWsAndComments {
+        line_comment: line_comment.to_string(),
+        block_open: block_comment.0.to_string(),
+        block_close: block_comment.1.to_string(),
+        nested: false,
+    }
+}
+
+pub struct SkipWsAndComments {
+    line_comment: String,
+    block_open: String,
+    block_close: String,
+    nested: bool,
+}
+
+impl SkipWsAndComments {
+    /// Allow block comments to nest, e.g. `/* a /* b */ c */`
+    pub fn nested(mut self) -> Self {
+        self.nested = true;
+        self
+    }
+
+    /// Skip the body of a block comment whose opening marker was already consumed
+    fn skip_block<'a>(&self, mut input: &'a str) -> ParseResult<&'a str, ()> {
+        let mut depth = 1;
+        while depth > 0 {
+            if let Some(rest) = input.strip_prefix(self.block_close.as_str()) {
+                depth -= 1;
+                input = rest;
+            } else if let Some(rest) = input
+                .strip_prefix(self.block_open.as_str())
+                .filter(|_| self.nested)
+            {
+                depth += 1;
+                input = rest;
+            } else {
+                match input.uncons() {
+                    Some((_, rest)) => input = rest,
+                    None => return Err(ParseError::incomplete(None)),
+                }
+            }
+        }
+        Ok(((), input))
+    }
+}
+
+impl<'a> Parser<&'a str, ()> for SkipWsAndComments {
+    fn parse(&self, mut input: &'a str) -> ParseResult<&'a str, ()> {
+        loop {
+            input = input.trim_start();
+            if !self.line_comment.is_empty()
+                && let Some(rest) = input.strip_prefix(self.line_comment.as_str())
+            {
+                input = rest.find('\n').map_or("", |end| &rest[end..]);
+            } else if !self.block_open.is_empty()
+                && let Some(rest) = input.strip_prefix(self.block_open.as_str())
+            {
+                input = self.skip_block(rest)?.1;
+            } else {
+                return Ok(((), input));
+            }
+        }
+    }
+}
+
+/// Parse from `open` to its matching `close`, returning the text in between
+///
+/// Nested pairs inside the body are skipped over, so `between_balanced('(', ')')`
+/// yields `a (b) c` from `(a (b) c)`. Running out of input before the match is
+/// `Incomplete`, recording how many closing delimiters are still needed.
+pub fn between_balanced(open: char, close: char) -> BetweenBalanced {
+    BetweenBalanced { open, close }
+}
+
+pub struct BetweenBalanced {
+    open: char,
+    close: char,
+}
+
+impl<'a> Parser<&'a str, &'a str> for BetweenBalanced {
+    fn parse(&self, input: &'a str) -> ParseResult<&'a str, &'a str> {
+        let (_, body) = token(self.open).parse(input)?;
+        let mut depth = 1;
+        for (index, c) in body.char_indices() {
+            if c == self.close {
+                depth -= 1;
+                if depth == 0 {
+                    return Ok((&body[..index], &body[index + c.len_utf8()..]));
+                }
+            } else if c == self.open {
+                depth += 1;
+            }
+        }
+        Err(ParseError::incomplete(Some(depth)))
+    }
+}
+
+/// Parse a specific string, then skip any trailing whitespace
+/// Composed using lexeme() and string()
+pub fn symbol<'a>(s: &str) -> impl Parser<&'a str, String> {
+    lexeme(string(s))
+}
+
+/// Parse a boolean literal, `true` or `false`
+/// Composed using keyword(), so `truex` does not match
+pub fn boolean<'a>() -> impl Parser<&'a str, bool> {
+    keyword("true")
+        .map(|_| true)
+        .or(keyword("false").map(|_| false))
+}
+
+/// Parse the literal `null`
+/// Composed using keyword(), so `nullable` does not match
+pub fn null<'a>() -> impl Parser<&'a str, ()> {
+    keyword("null").map(|_| ())
 }
 
 /// Parse a newline character
@@ -98,6 +497,183 @@ pub fn newline<'a>() -> impl Parser<&'a str, char> {
     char('\n')
 }
 
+/// Parse an end of line, either `\r\n` or `\n`
+///
+/// The two-character Windows form is tried first, so `\r\n` is always consumed
+/// as a single line ending rather than leaving a stray `\r` behind. A lone `\r`
+/// is not treated as a line ending.
+pub fn eol() -> Eol {
+    Eol
+}
+
+pub struct Eol;
+
+impl<'a> Parser<&'a str, &'a str> for Eol {
+    fn parse(&self, input: &'a str) -> ParseResult<&'a str, &'a str> {
+        let width = if input.starts_with("\r\n") {
+            2
+        } else if input.starts_with('\n') {
+            1
+        } else {
+            0
+        };
+        match input.uncons() {
+            Some(_) if width > 0 => Ok(input.split_at(width)),
+            Some((c, _)) => Err(ParseError::expected(
+                "end of line",
+                Some(format!("character '{}'", c)),
+                input,
+            )),
+            None => Err(ParseError::incomplete(Some(1))),
+        }
+    }
+}
+
+/// Parse the contents of a line, consuming its line ending
+///
+/// The returned slice excludes the `\n` or `\r\n` terminator. A final line
+/// without a trailing newline is returned as-is at end of input, while an
+/// already empty input fails so that repeating `line()` terminates.
+pub fn line() -> Line {
+    Line
+}
+
+pub struct Line;
+
+impl<'a> Parser<&'a str, &'a str> for Line {
+    fn parse(&self, input: &'a str) -> ParseResult<&'a str, &'a str> {
+        if input.is_empty() {
+            return Err(ParseError::incomplete(Some(1)));
+        }
+        match input.find('\n') {
+            Some(end) => {
+                let content = &input[..end];
+                Ok((
+                    content.strip_suffix('\r').unwrap_or(content),
+                    &input[end + 1..],
+                ))
+            }
+            None => Ok((input, "")),
+        }
+    }
+}
+
+/// Parse every line of the input with `row`, returning one result per line
+///
+/// `row` sees only the line's content, without its `\n` or `\r\n` terminator, and
+/// must consume all of it. A trailing newline at the end of the input does not
+/// produce an extra empty row. Errors point into the original input, and `row`
+/// running out of input is reported as "unexpected end of line".
+pub fn lines<P>(row: P) -> Lines<P> {
+    Lines {
+        row,
+        skip_blank: false,
+    }
+}
+
+pub struct Lines<P> {
+    row: P,
+    skip_blank: bool,
+}
+
+impl<P> Lines<P> {
+    /// Skip lines that are empty or contain only whitespace instead of parsing them
+    pub fn skip_blank(mut self) -> Self {
+        self.skip_blank = true;
+        self
+    }
+}
+
+impl<'a, T, P> Parser<&'a str, Vec<T>> for Lines<P>
+where
+    P: Parser<&'a str, T>,
+{
+    fn parse(&self, mut input: &'a str) -> ParseResult<&'a str, Vec<T>> {
+        let mut rows = Vec::new();
+        while let Ok((content, rest)) = line().parse(input) {
+            if !(self.skip_blank && content.trim().is_empty()) {
+                let (row, leftover) = self
+                    .row
+                    .parse(content)
+                    .map_err(|err| within_line(err, input, content.len()))?;
+                if !leftover.is_empty() {
+                    let trailing = line_position(input, content.len(), leftover);
+                    return Err(ParseError::message(
+                        "unexpected trailing input",
+                        trailing.unwrap_or(leftover),
+                    ));
+                }
+                rows.push(row);
+            }
+            input = rest;
+        }
+        Ok((rows, input))
+    }
+}
+
+/// Move an error from a line's content, which starts `input` and is `len` bytes
+/// long, onto the matching position of `input` itself
+fn within_line<'a>(err: ParseError<&'a str>, input: &'a str, len: usize) -> ParseError<&'a str> {
+    // Inputs the row parser made up itself fall back to the line start
+    let rebase = |rest: &'a str| line_position(input, len, rest).unwrap_or(input);
+    end_of_line(err.map_input(&rebase), &input[len..])
+}
+
+/// The position in `input` of `rest`, the end of a line's content that is `len`
+/// bytes long, or None if `rest` is not part of that content
+fn line_position<'a>(input: &'a str, len: usize, rest: &str) -> Option<&'a str> {
+    len.checked_sub(rest.len())
+        .and_then(|offset| input.get(offset..))
+}
+
+/// Report running out of a line's content at the line end, rather than as the
+/// end of the whole input
+fn end_of_line<'a>(err: ParseError<&'a str>, line_end: &'a str) -> ParseError<&'a str> {
+    match err {
+        ParseError::UnexpectedEof | ParseError::Incomplete { .. } => {
+            ParseError::message("unexpected end of line", line_end)
+        }
+        ParseError::Many(errors) => ParseError::Many(
+            errors
+                .into_iter()
+                .map(|err| end_of_line(err, line_end))
+                .collect(),
+        ),
+        ParseError::Context { context, error } => {
+            ParseError::context(context, end_of_line(*error, line_end))
+        }
+        ParseError::Named { names, error } => ParseError::Named {
+            names,
+            error: Box::new(end_of_line(*error, line_end)),
+        },
+        other => other,
+    }
+}
+
+/// Run a `&str` parser on the input underneath an `IgnoreWs`, so it sees
+/// whitespace, e.g. for string literals
+///
+/// Leading whitespace is skipped first, as for any other parser over `IgnoreWs`.
+pub fn raw<P>(parser: P) -> Raw<P> {
+    Raw { parser }
+}
+
+pub struct Raw<P> {
+    parser: P,
+}
+
+impl<'a, T, P> Parser<IgnoreWs<'a>, T> for Raw<P>
+where
+    P: Parser<&'a str, T>,
+{
+    fn parse(&self, input: IgnoreWs<'a>) -> ParseResult<IgnoreWs<'a>, T> {
+        self.parser
+            .parse(input.remaining().trim_start())
+            .map(|(value, rest)| (value, IgnoreWs::new(rest)))
+            .map_err(|err| err.map_input(&IgnoreWs::new))
+    }
+}
+
 /// Parse a tab character
 /// Composed using the char combinator
 pub fn tab<'a>() -> impl Parser<&'a str, char> {
@@ -142,22 +718,368 @@ impl<'a> Parser<&'a str, i32> for Integer {
     }
 }
 
+/// Parse an unsigned integer whose digits may be grouped with underscores, e.g. `1_000_000`
+///
+/// Underscores must sit between two digits; a leading, trailing, or doubled
+/// underscore is an error rather than the end of the number.
+pub fn unsigned_grouped() -> UnsignedGrouped {
+    UnsignedGrouped
+}
+
+pub struct UnsignedGrouped;
+
+impl<'a> Parser<&'a str, u32> for UnsignedGrouped {
+    fn parse(&self, input: &'a str) -> ParseResult<&'a str, u32> {
+        let (text, remaining) = match split_while(input, &|c| c.is_ascii_digit() || *c == '_') {
+            ("", _) => {
+                return Err(match input.uncons() {
+                    Some((c, _)) => {
+                        ParseError::expected("digit", Some(format!("character '{}'", c)), input)
+                    }
+                    None => ParseError::incomplete(Some(1)),
+                });
+            }
+            split => split,
+        };
+        if text.starts_with('_') || text.ends_with('_') || text.contains("__") {
+            return Err(ParseError::message("misplaced digit separator '_'", input));
+        }
+        match text.replace('_', "").parse::<u32>() {
+            Ok(n) => Ok((n, remaining)),
+            Err(_) => Err(ParseError::message("invalid number", input)),
+        }
+    }
+}
+
+/// Parse a signed integer whose digits may be grouped with underscores, e.g. `-1_000`
+pub fn integer_grouped() -> IntegerGrouped {
+    IntegerGrouped
+}
+
+pub struct IntegerGrouped;
+
+impl<'a> Parser<&'a str, i32> for IntegerGrouped {
+    fn parse(&self, input: &'a str) -> ParseResult<&'a str, i32> {
+        let ((sign, magnitude), remaining) =
+            char('-').optional().and(unsigned_grouped()).parse(input)?;
+        let value = match sign {
+            Some(_) => -i64::from(magnitude),
+            None => i64::from(magnitude),
+        };
+        match i32::try_from(value) {
+            Ok(value) => Ok((value, remaining)),
+            Err(_) => Err(ParseError::message("integer out of range", input)),
+        }
+    }
+}
+
+/// Which floating point forms `float_opts` accepts
+///
+/// The default is strict: `inf` and `nan` literals are rejected and a fractional
+/// part is optional, so `1`, `1.5`, `.5`, and `1e3` are all accepted.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FloatOptions {
+    /// Accept `inf` and `infinity`, case-insensitively and optionally signed, and
+    /// let literals too large for an `f64` overflow to infinity
+    pub allow_inf: bool,
+    /// Accept `nan`, case-insensitively and optionally signed
+    pub allow_nan: bool,
+    /// Require a `.` followed by at least one digit, rejecting integers like `1`
+    pub require_fraction: bool,
+}
+
+/// Parse a signed floating point number with the default (strict) `FloatOptions`
+pub fn float() -> Float {
+    float_opts(FloatOptions::default())
+}
+
+/// Parse a signed floating point number, with control over which forms are accepted
+///
+/// The number is scanned by `decimal_parts`, so an exponent that does not fit in
+/// an `i32` fails with "exponent out of range".
+pub fn float_opts(options: FloatOptions) -> Float {
+    Float { options }
+}
+
+pub struct Float {
+    options: FloatOptions,
+}
+
+impl Float {
+    /// Byte length of an accepted `inf`/`nan` literal at the start of `text`
+    fn special_len(&self, text: &str) -> Option<usize> {
+        let words = [
+            ("infinity", self.options.allow_inf),
+            ("inf", self.options.allow_inf),
+            ("nan", self.options.allow_nan),
+        ];
+        words.into_iter().find_map(|(word, allowed)| {
+            let prefix = text.get(..word.len())?;
+            (allowed && prefix.eq_ignore_ascii_case(word)).then_some(word.len())
+        })
+    }
+}
+
+/// Number of leading ASCII digits in `bytes`
+fn count_digits(bytes: &[u8]) -> usize {
+    bytes.iter().take_while(|b| b.is_ascii_digit()).count()
+}
+
+impl<'a> Parser<&'a str, f64> for Float {
+    fn parse(&self, input: &'a str) -> ParseResult<&'a str, f64> {
+        let sign = usize::from(matches!(input.as_bytes().first(), Some(b'+' | b'-')));
+        let end = match self.special_len(&input[sign..]) {
+            Some(len) => sign + len,
+            None => {
+                let (parts, remaining) = decimal_parts().parse(input)?;
+                if self.options.require_fraction && parts.frac_digits.is_empty() {
+                    return Err(ParseError::message("expected fractional part", input));
+                }
+                input.len() - remaining.len()
+            }
+        };
+        match input[..end].parse::<f64>() {
+            // A literal too large for an `f64`, such as `1e400`, overflows to infinity
+            Ok(n) if n.is_infinite() && !self.options.allow_inf => {
+                Err(ParseError::message("number out of range", input))
+            }
+            Ok(n) => Ok((n, &input[end..])),
+            Err(_) => Err(ParseError::message("invalid number", input)),
+        }
+    }
+}
+
+/// The pieces of a decimal number kept as written, as produced by `decimal_parts`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DecimalParts<'a> {
+    /// The explicit `+` or `-` sign, if one was written
+    pub sign: Option<char>,
+    /// Digits before the decimal point, possibly empty as in `.5`
+    pub int_digits: &'a str,
+    /// Digits after the decimal point, possibly empty as in `1` or `1.`
+    pub frac_digits: &'a str,
+    /// The exponent value, if an exponent with at least one digit was written
+    pub exp: Option<i32>,
+}
+
+/// Parse a decimal number into its sign, digit runs and exponent without rounding
+///
+/// Accepts the same spellings as `float()`, for handing off to an arbitrary-precision
+/// decimal type where an `f64` would lose digits.
+pub fn decimal_parts() -> DecimalPartsParser {
+    DecimalPartsParser
+}
+
+pub struct DecimalPartsParser;
+
+impl<'a> Parser<&'a str, DecimalParts<'a>> for DecimalPartsParser {
+    fn parse(&self, input: &'a str) -> ParseResult<&'a str, DecimalParts<'a>> {
+        let bytes = input.as_bytes();
+        let sign = match bytes.first() {
+            Some(b'+') => Some('+'),
+            Some(b'-') => Some('-'),
+            _ => None,
+        };
+        let int_start = usize::from(sign.is_some());
+        let int_end = int_start + count_digits(&bytes[int_start..]);
+        let mut end = int_end;
+        let mut frac_digits = "";
+        if bytes.get(end) == Some(&b'.') {
+            let frac_end = end + 1 + count_digits(&bytes[end + 1..]);
+            if int_end > int_start || frac_end > end + 1 {
+                frac_digits = &input[end + 1..frac_end];
+                end = frac_end;
+            }
+        }
+        if int_end == int_start && frac_digits.is_empty() {
+            return Err(match input[end..].chars().next() {
+                Some(c) => {
+                    ParseError::expected("number", Some(format!("character '{}'", c)), input)
+                }
+                None => ParseError::incomplete(Some(1)),
+            });
+        }
+
+        // An exponent is only consumed if it has at least one digit
+        let mut exp = None;
+        if matches!(bytes.get(end), Some(b'e' | b'E')) {
+            let digits_start =
+                end + 1 + usize::from(matches!(bytes.get(end + 1), Some(b'+' | b'-')));
+            let digits_end = digits_start + count_digits(&bytes[digits_start..]);
+            if digits_end > digits_start {
+                match input[end + 1..digits_end].parse::<i32>() {
+                    Ok(value) => exp = Some(value),
+                    Err(_) => return Err(ParseError::message("exponent out of range", input)),
+                }
+                end = digits_end;
+            }
+        }
+
+        let parts = DecimalParts {
+            sign,
+            int_digits: &input[int_start..int_end],
+            frac_digits,
+            exp,
+        };
+        Ok((parts, &input[end..]))
+    }
+}
+
+/// A number whose kind is decided by its spelling, as produced by `json_number`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Number {
+    /// No fractional part or exponent, e.g. `-12`
+    Int(i64),
+    /// A fractional part or exponent was present, e.g. `1.5` or `1e3`
+    Float(f64),
+}
+
+/// Which number forms `json_number_opts` accepts beyond the JSON grammar
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct JsonNumberOptions {
+    /// Accept integer parts with leading zeros, such as `007`
+    pub allow_leading_zeros: bool,
+}
+
+/// Parse a JSON number with the default (strict) `JsonNumberOptions`
+pub fn json_number() -> JsonNumber {
+    json_number_opts(JsonNumberOptions::default())
+}
+
+/// Parse a JSON number into `Number::Int` or `Number::Float`
+///
+/// Follows the JSON grammar: an optional `-` (never `+`), an integer part, then an
+/// optional fraction and exponent, each only consumed when followed by digits. A
+/// value that does not fit its type fails with "number out of range".
+pub fn json_number_opts(options: JsonNumberOptions) -> JsonNumber {
+    JsonNumber { options }
+}
+
+pub struct JsonNumber {
+    options: JsonNumberOptions,
+}
+
+impl<'a> Parser<&'a str, Number> for JsonNumber {
+    fn parse(&self, input: &'a str) -> ParseResult<&'a str, Number> {
+        let not_a_number =
+            |c: char| ParseError::expected("number", Some(format!("character '{}'", c)), input);
+        let (parts, remaining) = decimal_parts().parse(input)?;
+        // JSON has no `+` sign and no integer part may be left out, as in `.5`
+        if parts.sign == Some('+') {
+            return Err(not_a_number('+'));
+        }
+        let int_start = usize::from(parts.sign.is_some());
+        if parts.int_digits.is_empty() {
+            return Err(not_a_number('.'));
+        }
+        if parts.int_digits.len() > 1
+            && parts.int_digits.starts_with('0')
+            && !self.options.allow_leading_zeros
+        {
+            return Err(ParseError::message("leading zeros are not allowed", input));
+        }
+
+        // A `.` must be followed by digits, so `1.` and `1.e5` stop before the `.`
+        let int_end = int_start + parts.int_digits.len();
+        let end = if parts.frac_digits.is_empty() && input[int_end..].starts_with('.') {
+            int_end
+        } else {
+            input.len() - remaining.len()
+        };
+        let text = &input[..end];
+        let number = if end > int_end {
+            text.parse::<f64>()
+                .ok()
+                .filter(|n| n.is_finite())
+                .map(Number::Float)
+        } else {
+            text.parse::<i64>().ok().map(Number::Int)
+        };
+        match number {
+            Some(number) => Ok((number, &input[end..])),
+            None => Err(ParseError::message("number out of range", input)),
+        }
+    }
+}
+
 /// Parse any character except the given one
-/// Composed using the satisfy combinator
+/// Composed using the satisfy_labeled combinator
 pub fn not_char<'a>(c: char) -> impl Parser<&'a str, char> {
-    satisfy(move |ch: &char| *ch != c)
+    satisfy_labeled(format!("any character except {:?}", c), move |ch: &char| {
+        *ch != c
+    })
 }
 
 /// Parse any character from a given set
-/// Composed using the satisfy combinator
+/// Composed using char_set, so membership is a constant-time lookup
 pub fn one_of<'a>(chars: &str) -> impl Parser<&'a str, char> {
-    let chars = chars.to_string();
-    satisfy(move |c: &char| chars.contains(*c))
+    char_set(chars)
+}
+
+/// Parse any character from a given set, built once into a lookup table
+///
+/// ASCII characters are checked against a 128-bit bitmap and others against a
+/// hash set, so matching costs the same however many characters the set holds.
+pub fn char_set(chars: &str) -> CharSet {
+    let mut ascii = 0u128;
+    let mut other = HashSet::new();
+    for c in chars.chars() {
+        if c.is_ascii() {
+            ascii |= 1 << c as u32;
+        } else {
+            other.insert(c);
+        }
+    }
+    CharSet {
+        ascii,
+        other,
+        chars: chars.to_string(),
+        negated: false,
+    }
+}
+
+pub struct CharSet {
+    ascii: u128,
+    other: HashSet<char>,
+    chars: String,
+    /// Match characters outside the set instead, for `none_of`
+    negated: bool,
+}
+
+impl CharSet {
+    fn matches(&self, c: char) -> bool {
+        let contains = if c.is_ascii() {
+            self.ascii & (1 << c as u32) != 0
+        } else {
+            self.other.contains(&c)
+        };
+        contains != self.negated
+    }
+}
+
+impl<'a> Parser<&'a str, char> for CharSet {
+    fn parse(&self, input: &'a str) -> ParseResult<&'a str, char> {
+        match input.uncons() {
+            Some((c, remaining)) if self.matches(c) => Ok((c, remaining)),
+            Some((c, _)) => Err(ParseError::expected(
+                format!(
+                    "{} of {:?}",
+                    if self.negated { "none" } else { "one" },
+                    self.chars
+                ),
+                Some(format!("{:?}", c)),
+                input,
+            )),
+            None => Err(ParseError::incomplete(Some(1))),
+        }
+    }
 }
 
 /// Parse any character not in the given set
-/// Composed using the satisfy combinator
+/// Built on the same lookup table as char_set
 pub fn none_of<'a>(chars: &str) -> impl Parser<&'a str, char> {
-    let chars = chars.to_string();
-    satisfy(move |c: &char| !chars.contains(*c))
+    CharSet {
+        negated: true,
+        ..char_set(chars)
+    }
 }
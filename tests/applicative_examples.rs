@@ -117,7 +117,7 @@ fn test_list_parsing() {
 fn test_simple_person_parsing() {
     let parser = alpha()
         .many1()
-        .map(|chars| chars.into_iter().collect::<String>())
+        .collect_string()
         .skip(spaces())
         .and(unsigned())
         .map(|(name, age)| Person { name, age });
@@ -136,3 +136,121 @@ fn test_simple_person_parsing() {
     )
     "###);
 }
+
+// Same addition as above, with whitespace handled by lexeme/symbol
+#[test]
+fn test_add_parser_with_lexemes() {
+    let parser = lexeme(unsigned())
+        .skip(symbol("+"))
+        .and(lexeme(unsigned()))
+        .map(|(a, b)| a + b);
+
+    let result = parser.parse("42 +   13  ");
+
+    insta::assert_debug_snapshot!(result, @r#"
+    Ok(
+        (
+            55,
+            "",
+        ),
+    )
+    "#);
+}
+
+// Same RGB parsing, sequencing every piece with tuple() instead of reshaping nested pairs
+#[test]
+fn test_rgb_parsing_with_tuple() {
+    let parser = tuple((
+        string("rgb("),
+        unsigned(),
+        symbol(","),
+        unsigned(),
+        symbol(","),
+        unsigned(),
+        char(')'),
+    ))
+    .map(|(_, r, _, g, _, b, _)| (r, g, b));
+
+    let result = parser.parse("rgb(255, 128, 64)");
+
+    insta::assert_debug_snapshot!(result, @r#"
+    Ok(
+        (
+            (
+                255,
+                128,
+                64,
+            ),
+            "",
+        ),
+    )
+    "#);
+}
+
+// tuple() stops at the first failing parser
+#[test]
+fn test_tuple_short_circuits() {
+    let parser = tuple((digit(), alpha(), digit()));
+
+    let result = parser.parse("1x?");
+
+    insta::assert_debug_snapshot!(result, @r#"
+    Err(
+        Expected {
+            expected: "digit",
+            found: Some(
+                "'?'",
+            ),
+            input: "?",
+        },
+    )
+    "#);
+}
+
+// apply() flattens an `and` chain into separate closure arguments
+#[test]
+fn test_date_parsing_with_apply() {
+    let parser = unsigned()
+        .skip(char('-'))
+        .and(unsigned())
+        .skip(char('-'))
+        .and(unsigned())
+        .skip(char('T'))
+        .and(unsigned())
+        .skip(char(':'))
+        .and(unsigned())
+        .apply(|year, month, day, hour, minute| {
+            format!("{year:04}/{month:02}/{day:02} {hour:02}h{minute:02}")
+        });
+
+    let result = parser.parse("2024-3-9T7:05");
+
+    insta::assert_debug_snapshot!(result, @r#"
+    Ok(
+        (
+            "2024/03/09 07h05",
+            "",
+        ),
+    )
+    "#);
+}
+
+// Closure parameters need annotations when the body calls methods on them
+#[test]
+fn test_key_value_with_apply() {
+    let parser = identifier()
+        .skip(char('='))
+        .and(unsigned())
+        .apply(|key: &str, value| format!("{}:{}", key.to_uppercase(), value + 1));
+
+    let result = parser.parse("port=8079");
+
+    insta::assert_debug_snapshot!(result, @r#"
+    Ok(
+        (
+            "PORT:8080",
+            "",
+        ),
+    )
+    "#);
+}
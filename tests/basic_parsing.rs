@@ -20,11 +20,15 @@ fn test_item_parser() {
 fn test_item_parser_empty() {
     let parser = item();
     let result = parser.parse("");
-    insta::assert_debug_snapshot!(result, @r###"
+    insta::assert_debug_snapshot!(result, @r"
     Err(
-        UnexpectedEof,
+        Incomplete {
+            needed: Some(
+                1,
+            ),
+        },
     )
-    "###);
+    ");
 }
 
 #[test]
@@ -50,7 +54,7 @@ fn test_satisfy_failure() {
         Expected {
             expected: "item satisfying predicate",
             found: Some(
-                "different item",
+                "'h'",
             ),
             input: "hello",
         },
@@ -58,6 +62,23 @@ fn test_satisfy_failure() {
     "#);
 }
 
+#[test]
+fn test_satisfy_labeled_failure() {
+    let parser = satisfy_labeled("hex digit", char::is_ascii_hexdigit);
+    let result = parser.parse("xyz");
+    insta::assert_debug_snapshot!(result, @r#"
+    Err(
+        Expected {
+            expected: "hex digit",
+            found: Some(
+                "'x'",
+            ),
+            input: "xyz",
+        },
+    )
+    "#);
+}
+
 #[test]
 fn test_token_success() {
     let parser = token('h');
@@ -116,3 +137,559 @@ fn test_fail_parser() {
     )
     "#);
 }
+
+#[test]
+fn test_assert_next_consumes_nothing() {
+    let parser = assert_next(|c: &char| c.is_ascii_digit());
+    insta::assert_debug_snapshot!((parser.parse("42"), parser.parse("x2")), @r#"
+    (
+        Ok(
+            (
+                (),
+                "42",
+            ),
+        ),
+        Err(
+            Expected {
+                expected: "item satisfying predicate",
+                found: Some(
+                    "'x'",
+                ),
+                input: "x2",
+            },
+        ),
+    )
+    "#);
+}
+
+#[test]
+fn test_satisfy_map_success() {
+    let parser = satisfy_map(|c: &char| c.to_digit(10));
+    let result = parser.parse("7up");
+    insta::assert_debug_snapshot!(result, @r#"
+    Ok(
+        (
+            7,
+            "up",
+        ),
+    )
+    "#);
+}
+
+#[test]
+fn test_satisfy_map_failure() {
+    let parser = satisfy_map(|c: &char| c.to_digit(10));
+    let result = parser.parse("up");
+    insta::assert_debug_snapshot!(result, @r#"
+    Err(
+        Expected {
+            expected: "item satisfying predicate",
+            found: Some(
+                "'u'",
+            ),
+            input: "up",
+        },
+    )
+    "#);
+}
+
+#[test]
+fn test_parse_complete_success() {
+    let result = parse_complete(&integer(), "-42");
+    insta::assert_debug_snapshot!(result, @r"
+    Ok(
+        -42,
+    )
+    ");
+}
+
+#[test]
+fn test_parse_complete_trailing_input() {
+    let result = parse_complete(&integer(), "-42abc");
+    insta::assert_debug_snapshot!(result, @r#"
+    Err(
+        Message {
+            message: "unexpected trailing input",
+            input: "abc",
+        },
+    )
+    "#);
+}
+
+#[test]
+fn test_parse_result_ext() {
+    let value = integer().parse("-42abc").value();
+    let doubled = integer().parse("21;").map_value(|n| n * 2);
+    let remaining_len = integer().parse("7 rest").map_remaining(str::len);
+    insta::assert_debug_snapshot!((value, doubled, remaining_len), @r#"
+    (
+        Ok(
+            -42,
+        ),
+        Ok(
+            (
+                42,
+                ";",
+            ),
+        ),
+        Ok(
+            (
+                7,
+                5,
+            ),
+        ),
+    )
+    "#);
+}
+
+#[test]
+fn test_end_trailing_input() {
+    let parser = integer().end();
+    insta::assert_debug_snapshot!((parser.parse("-42"), parser.parse("-42abc")), @r#"
+    (
+        Ok(
+            (
+                -42,
+                "",
+            ),
+        ),
+        Err(
+            Message {
+                message: "unexpected trailing input",
+                input: "abc",
+            },
+        ),
+    )
+    "#);
+}
+
+#[test]
+fn test_count_consumed() {
+    let parser = integer().count_consumed();
+    let unknown_len = item().count_consumed().parse(IterInput::new(vec![1, 2]));
+    insta::assert_debug_snapshot!((parser.parse("-42abc"), unknown_len.is_err()), @r#"
+    (
+        Ok(
+            (
+                (
+                    -42,
+                    3,
+                ),
+                "abc",
+            ),
+        ),
+        true,
+    )
+    "#);
+}
+
+#[test]
+fn test_parse_complete_eof() {
+    let result = parse_complete(&string("hello"), "he");
+    insta::assert_debug_snapshot!(result, @r"
+    Err(
+        UnexpectedEof,
+    )
+    ");
+}
+
+#[test]
+fn test_run_returns_named_struct() {
+    let result = unsigned().run("42 rest");
+    insta::assert_debug_snapshot!(result, @r#"
+    Ok(
+        Parsed {
+            value: 42,
+            remaining: " rest",
+        },
+    )
+    "#);
+}
+
+#[test]
+fn test_parsed_accessors() {
+    let parsed = unsigned().run("42 rest").unwrap();
+    let summary = format!("{} / {:?}", parsed.value(), parsed.remaining());
+    insta::assert_snapshot!(summary, @r#"42 / " rest""#);
+}
+
+#[test]
+fn test_collect_string() {
+    let parser = alpha().many1().collect_string();
+    insta::assert_debug_snapshot!(parser.parse("abc1"), @r#"
+    Ok(
+        (
+            "abc",
+            "1",
+        ),
+    )
+    "#);
+}
+
+#[test]
+fn test_deque_input() {
+    let items: std::collections::VecDeque<u32> = (1..=5).collect();
+    let parser = take(3).map(|prefix: DequeInput<u32>| prefix.iter().sum::<u32>());
+    let result = parser
+        .parse(DequeInput::new(items))
+        .map(|(sum, remaining)| (sum, remaining.position(), remaining.len()));
+    insta::assert_debug_snapshot!(result, @r"
+    Ok(
+        (
+            6,
+            3,
+            Some(
+                2,
+            ),
+        ),
+    )
+    ");
+}
+
+#[test]
+fn test_deque_input_backtracking() {
+    let items: std::collections::VecDeque<char> = "abc".chars().collect();
+    let parser = token('a')
+        .and(token('x'))
+        .map(|_| 0)
+        .or(item().many().map(|v| v.len()));
+    let result = parser
+        .parse(DequeInput::new(items))
+        .map(|(count, remaining)| (count, remaining.is_empty()));
+    insta::assert_debug_snapshot!(result, @r"
+    Ok(
+        (
+            3,
+            true,
+        ),
+    )
+    ");
+}
+
+#[test]
+fn test_iter_input_backtracking() {
+    let tokens = "let x".split(' ').map(str::to_string);
+    let keyword = |s: &'static str| satisfy(move |t: &String| t == s);
+    let parser = keyword("let")
+        .and(keyword("mut"))
+        .map(|_| "mutable")
+        .or(keyword("let").map(|_| "immutable"))
+        .and(item());
+    let result = parser
+        .parse(IterInput::new(tokens))
+        .map(|(value, remaining)| (value, remaining.position()));
+    insta::assert_debug_snapshot!(result, @r#"
+    Ok(
+        (
+            (
+                "immutable",
+                "x",
+            ),
+            2,
+        ),
+    )
+    "#);
+}
+
+#[test]
+fn test_input_without_partial_eq() {
+    // A cursor over the children of a DOM-like node, with no structural equality
+    #[derive(Debug, Clone)]
+    struct Children {
+        tags: std::rc::Rc<[&'static str]>,
+        index: usize,
+    }
+
+    impl Input for Children {
+        type Item = &'static str;
+
+        fn uncons(&self) -> Option<(Self::Item, Self)> {
+            let tag = *self.tags.get(self.index)?;
+            let rest = Children {
+                index: self.index + 1,
+                ..self.clone()
+            };
+            Some((tag, rest))
+        }
+
+        fn len(&self) -> Option<usize> {
+            Some(self.tags.len() - self.index)
+        }
+
+        fn same_position(&self, other: &Self) -> bool {
+            self.index == other.index
+        }
+    }
+
+    let children = Children {
+        tags: std::rc::Rc::from(["li", "li", "p"]),
+        index: 0,
+    };
+    let parser = token("li").many().and(token("p").optional().many());
+    let (result, rest) = parser.parse(children).unwrap();
+    insta::assert_debug_snapshot!((result, rest.index), @r#"
+    (
+        (
+            [
+                "li",
+                "li",
+            ],
+            [
+                Some(
+                    "p",
+                ),
+            ],
+        ),
+        3,
+    )
+    "#);
+}
+
+#[test]
+fn test_input_without_len_stops_zero_width_repetition() {
+    // A cursor that knows neither its length nor how to compare whole inputs
+    #[derive(Debug, Clone)]
+    struct Cursor {
+        words: std::rc::Rc<[&'static str]>,
+        index: usize,
+    }
+
+    impl Input for Cursor {
+        type Item = &'static str;
+
+        fn uncons(&self) -> Option<(Self::Item, Self)> {
+            let word = *self.words.get(self.index)?;
+            let rest = Cursor {
+                index: self.index + 1,
+                ..self.clone()
+            };
+            Some((word, rest))
+        }
+
+        fn same_position(&self, other: &Self) -> bool {
+            self.index == other.index
+        }
+    }
+
+    let cursor = Cursor {
+        words: std::rc::Rc::from(["a", "a", "b"]),
+        index: 0,
+    };
+    let parser = token("a").optional().many();
+    let (result, rest) = parser.parse(cursor).unwrap();
+    insta::assert_debug_snapshot!((result, rest.index), @r#"
+    (
+        [
+            Some(
+                "a",
+            ),
+            Some(
+                "a",
+            ),
+        ],
+        2,
+    )
+    "#);
+}
+
+#[test]
+fn test_iter_input_take() {
+    let (prefix, rest) = take(3).parse(IterInput::new(1..)).unwrap();
+    let (empty, rest) = take(0).parse(rest).unwrap();
+    let (items, _) = item().many().parse(prefix).unwrap();
+    let (next, _) = item().parse(rest).unwrap();
+    insta::assert_debug_snapshot!((items, empty.is_empty(), next), @r"
+    (
+        [
+            1,
+            2,
+            3,
+        ],
+        true,
+        4,
+    )
+    ");
+}
+
+#[test]
+fn test_iter_input_pulls_lazily() {
+    let pulled = std::rc::Rc::new(std::cell::Cell::new(0));
+    let counter = pulled.clone();
+    let numbers = (1..).inspect(move |_| counter.set(counter.get() + 1));
+    let (result, _) = item().and(item()).parse(IterInput::new(numbers)).unwrap();
+    insta::assert_debug_snapshot!((result, pulled.get()), @r"
+    (
+        (
+            1,
+            2,
+        ),
+        2,
+    )
+    ");
+}
+
+#[test]
+fn test_token_slice_input() {
+    #[derive(Debug, Clone, PartialEq)]
+    enum Token {
+        Num(i64),
+        Plus,
+    }
+
+    let tokens = [Token::Num(1), Token::Plus, Token::Num(2), Token::Plus];
+    let number = satisfy_map(|t: &Token| match t {
+        Token::Num(n) => Some(*n),
+        _ => None,
+    });
+    let parser = fold_sep_by1(number, token(Token::Plus), 0, |acc, n| acc + n);
+    let result = parser.parse(&tokens[..]);
+    insta::assert_debug_snapshot!(result, @r"
+    Ok(
+        (
+            3,
+            [
+                Plus,
+            ],
+        ),
+    )
+    ");
+}
+
+#[test]
+fn test_token_ref_borrows_expected() {
+    #[derive(Debug, Clone, PartialEq)]
+    enum Token {
+        Ident(String),
+        Comma,
+    }
+
+    let expected = Token::Ident("width".to_string());
+    let tokens = [
+        expected.clone(),
+        Token::Comma,
+        Token::Ident("height".to_string()),
+    ];
+    let parser = token_ref(&expected)
+        .skip(token(Token::Comma))
+        .and(token_ref(&expected));
+    let result = parser.parse(&tokens[..]);
+    insta::assert_debug_snapshot!(result, @r#"
+    Err(
+        Expected {
+            expected: "Ident(\"width\")",
+            found: Some(
+                "Ident(\"height\")",
+            ),
+            input: [
+                Ident(
+                    "height",
+                ),
+            ],
+        },
+    )
+    "#);
+}
+
+#[test]
+fn test_str_input_line_and_column() {
+    let line = satisfy(|c: &char| *c != '\n').many1().skip(token('\n'));
+    let parser = line.many1().and(item());
+    let (_, remaining) = parser.parse(StrInput::new("ab\nçd\ne")).unwrap();
+    let position = (remaining.line(), remaining.column(), remaining.offset());
+    insta::assert_debug_snapshot!(position, @r"
+    (
+        3,
+        2,
+        8,
+    )
+    ");
+}
+
+#[test]
+fn test_ignore_ws_input() {
+    let name = satisfy(|c: &char| c.is_alphabetic())
+        .many1()
+        .collect_string();
+    let literal = raw(take_while(|c: &char| *c != '"').delimited_by(char('"'), char('"')));
+    let parser = name.skip(token('=')).and(literal).skip(token(';'));
+    let result = parser.parse(IgnoreWs::new("  key =  \"a b\" ;  "));
+    insta::assert_debug_snapshot!(result.map(|(value, rest)| (value, rest.is_empty())), @r#"
+    Ok(
+        (
+            (
+                "key",
+                "a b",
+            ),
+            true,
+        ),
+    )
+    "#);
+}
+
+#[test]
+fn test_str_input_counts_chars() {
+    let (_, remaining) = take(2).parse(StrInput::new("e\u{301}x")).unwrap();
+    insta::assert_debug_snapshot!((remaining.column(), remaining.remaining()), @r#"
+    (
+        3,
+        "x",
+    )
+    "#);
+}
+
+#[cfg(feature = "graphemes")]
+#[test]
+fn test_str_input_counts_graphemes() {
+    let (_, remaining) = take(2)
+        .parse(StrInput::with_graphemes("e\u{301}x"))
+        .unwrap();
+    insta::assert_debug_snapshot!((remaining.column(), remaining.remaining()), @r#"
+    (
+        2,
+        "x",
+    )
+    "#);
+}
+
+#[test]
+fn test_consumed_since() {
+    let source = "let x = 1;";
+    let (rest, bytes_rest) = (&source[4..], &source.as_bytes()[8..]);
+    let unrelated = String::from("x = 1;");
+    let located = Located::new(source);
+    let (_, located_rest) = take(4).parse(located.clone()).unwrap();
+    let results = (
+        rest.consumed_since(&source),
+        bytes_rest.consumed_since(&source.as_bytes()),
+        unrelated.as_str().consumed_since(&source),
+        located_rest
+            .consumed_since(&located)
+            .map(|prefix| (prefix.position(), *prefix.input())),
+    );
+    insta::assert_debug_snapshot!(results, @r#"
+    (
+        Some(
+            "let ",
+        ),
+        Some(
+            [
+                108,
+                101,
+                116,
+                32,
+                120,
+                32,
+                61,
+                32,
+            ],
+        ),
+        None,
+        Some(
+            (
+                0,
+                "let ",
+            ),
+        ),
+    )
+    "#);
+}
@@ -0,0 +1,260 @@
+//! Byte-oriented parser tests using snapshot testing
+
+use simons_parser::bytes::*;
+use simons_parser::*;
+
+#[test]
+fn test_tag_success() {
+    let parser = tag(b"GET ");
+    let result = parser
+        .parse(&b"GET /index.html"[..])
+        .map(|(matched, rest)| {
+            (
+                matched.escape_ascii().to_string(),
+                rest.escape_ascii().to_string(),
+            )
+        });
+    insta::assert_debug_snapshot!(result, @r#"
+    Ok(
+        (
+            "GET ",
+            "/index.html",
+        ),
+    )
+    "#);
+}
+
+#[test]
+fn test_tag_failure() {
+    let parser = tag(b"GET");
+    let result = parser.parse(&b"G\x00T"[..]);
+    insta::assert_debug_snapshot!(result, @r#"
+    Err(
+        Expected {
+            expected: "tag 'GET'",
+            found: Some(
+                "byte '\\x00'",
+            ),
+            input: [
+                71,
+                0,
+                84,
+            ],
+        },
+    )
+    "#);
+}
+
+#[test]
+fn test_tag_incomplete() {
+    let parser = tag(b"HTTP/1.1");
+    let result = parser.parse(&b"HTTP"[..]);
+    insta::assert_debug_snapshot!(result, @r"
+    Err(
+        Incomplete {
+            needed: Some(
+                4,
+            ),
+        },
+    )
+    ");
+}
+
+#[test]
+fn test_ascii_ci() {
+    let parser = ascii_ci(b"content-length");
+    let result = parser
+        .parse(&b"Content-Length: 5"[..])
+        .map(|(matched, rest)| {
+            (
+                matched.escape_ascii().to_string(),
+                rest.escape_ascii().to_string(),
+            )
+        });
+    insta::assert_debug_snapshot!(result, @r#"
+    Ok(
+        (
+            "Content-Length",
+            ": 5",
+        ),
+    )
+    "#);
+}
+
+#[test]
+fn test_byte_classes() {
+    let parser = alpha_b().many1().and(space_b()).and(digit_b().many1());
+    let result = parser.parse(&b"ab 42"[..]);
+    insta::assert_debug_snapshot!(result, @r"
+    Ok(
+        (
+            (
+                (
+                    [
+                        97,
+                        98,
+                    ],
+                    32,
+                ),
+                [
+                    52,
+                    50,
+                ],
+            ),
+            [],
+        ),
+    )
+    ");
+}
+
+#[test]
+fn test_length_count() {
+    let parser = length_count(item(), item());
+    let results = (
+        parser.parse(&[3u8, 10, 20, 30, 99][..]),
+        parser.parse(&[3u8, 10][..]),
+    );
+    insta::assert_debug_snapshot!(results, @r"
+    (
+        Ok(
+            (
+                [
+                    10,
+                    20,
+                    30,
+                ],
+                [
+                    99,
+                ],
+            ),
+        ),
+        Err(
+            Incomplete {
+                needed: Some(
+                    1,
+                ),
+            },
+        ),
+    )
+    ");
+}
+
+#[test]
+fn test_length_data() {
+    let parser = length_data(item());
+    let result = parser.parse(&b"\x03abcde"[..]);
+    insta::assert_debug_snapshot!(result, @r"
+    Ok(
+        (
+            [
+                97,
+                98,
+                99,
+            ],
+            [
+                100,
+                101,
+            ],
+        ),
+    )
+    ");
+}
+
+#[test]
+fn test_length_value_requires_whole_frame() {
+    let field = length_value(item(), digit_b().many1());
+    let results = (
+        field.parse(&b"\x0212\x013"[..]),
+        field.parse(&b"\x031x2"[..]),
+    );
+    insta::assert_debug_snapshot!(results, @r#"
+    (
+        Ok(
+            (
+                [
+                    49,
+                    50,
+                ],
+                [
+                    1,
+                    51,
+                ],
+            ),
+        ),
+        Err(
+            Message {
+                message: "unexpected trailing input",
+                input: [
+                    120,
+                    50,
+                ],
+            },
+        ),
+    )
+    "#);
+}
+
+#[test]
+fn test_endian_integers() {
+    use simons_parser::bytes::number::*;
+    let header = be_u16().and(le_u32()).and(be_i16());
+    let results = (
+        header.parse(&[0x12, 0x34, 0x78, 0x56, 0x34, 0x12, 0xff, 0xfe, 0xaa][..]),
+        be_u64().parse(&[0x00, 0x01][..]),
+    );
+    insta::assert_debug_snapshot!(results, @r"
+    (
+        Ok(
+            (
+                (
+                    (
+                        4660,
+                        305419896,
+                    ),
+                    -2,
+                ),
+                [
+                    170,
+                ],
+            ),
+        ),
+        Err(
+            UnexpectedEof,
+        ),
+    )
+    ");
+}
+
+#[test]
+fn test_byte_set() {
+    let parser = byte_set(b"\r\n\t \xff").many1();
+    let results = (parser.parse(&b"\t \xff\nx"[..]), parser.parse(&b"x"[..]));
+    insta::assert_debug_snapshot!(results, @r#"
+    (
+        Ok(
+            (
+                [
+                    9,
+                    32,
+                    255,
+                    10,
+                ],
+                [
+                    120,
+                ],
+            ),
+        ),
+        Err(
+            Expected {
+                expected: "one of '\\r\\n\\t \\xff'",
+                found: Some(
+                    "byte 'x'",
+                ),
+                input: [
+                    120,
+                ],
+            },
+        ),
+    )
+    "#);
+}
@@ -214,6 +214,126 @@ fn test_many1_failure() {
     "#);
 }
 
+#[test]
+fn test_repeat_ranges() {
+    let input = "12345x";
+    let results = (
+        digit().repeat(..).parse(input),
+        digit().repeat(3).parse(input),
+        digit().repeat(2..=4).parse(input),
+        digit().repeat(..2).parse(input),
+    );
+    insta::assert_debug_snapshot!(results, @r#"
+    (
+        Ok(
+            (
+                [
+                    '1',
+                    '2',
+                    '3',
+                    '4',
+                    '5',
+                ],
+                "x",
+            ),
+        ),
+        Ok(
+            (
+                [
+                    '1',
+                    '2',
+                    '3',
+                ],
+                "45x",
+            ),
+        ),
+        Ok(
+            (
+                [
+                    '1',
+                    '2',
+                    '3',
+                    '4',
+                ],
+                "5x",
+            ),
+        ),
+        Ok(
+            (
+                [
+                    '1',
+                ],
+                "2345x",
+            ),
+        ),
+    )
+    "#);
+}
+
+#[test]
+fn test_repeat_too_few() {
+    let parser = digit().repeat(3..);
+    let result = parser.parse("12x");
+    insta::assert_debug_snapshot!(result, @r#"
+    Err(
+        Expected {
+            expected: "digit",
+            found: Some(
+                "'x'",
+            ),
+            input: "x",
+        },
+    )
+    "#);
+}
+
+#[test]
+fn test_fold_many_m_n_builds_number() {
+    let parser = digit().fold_many_m_n(2, 4, 0u32, |n, d| n * 10 + d.to_digit(10).unwrap());
+    let results = (parser.parse("123456"), parser.parse("7x"));
+    insta::assert_debug_snapshot!(results, @r#"
+    (
+        Ok(
+            (
+                1234,
+                "56",
+            ),
+        ),
+        Err(
+            Expected {
+                expected: "digit",
+                found: Some(
+                    "'x'",
+                ),
+                input: "x",
+            },
+        ),
+    )
+    "#);
+}
+
+#[test]
+fn test_opt_preceded_by_and_opt_skip() {
+    let parser = unsigned().opt_preceded_by(char('+')).opt_skip(char(';'));
+    let results = (parser.parse("+12;"), parser.parse("12 rest"));
+    insta::assert_debug_snapshot!(results, @r#"
+    (
+        Ok(
+            (
+                12,
+                "",
+            ),
+        ),
+        Ok(
+            (
+                12,
+                " rest",
+            ),
+        ),
+    )
+    "#);
+}
+
 #[test]
 fn test_between() {
     let parser = between(token('('), item(), token(')'));
@@ -228,6 +348,23 @@ fn test_between() {
     "###);
 }
 
+#[test]
+fn test_delimited_by() {
+    let parser = item().delimited_by(token('('), token(')')).many1();
+    let result = parser.parse("(x)(y)z");
+    insta::assert_debug_snapshot!(result, @r#"
+    Ok(
+        (
+            [
+                'x',
+                'y',
+            ],
+            "z",
+        ),
+    )
+    "#);
+}
+
 #[test]
 fn test_choice() {
     let parsers = vec![token('a'), token('b'), token('c')];
@@ -315,7 +452,11 @@ fn test_sep_by1_failure() {
     let result = parser.parse("");
     insta::assert_debug_snapshot!(result, @r"
     Err(
-        UnexpectedEof,
+        Incomplete {
+            needed: Some(
+                1,
+            ),
+        },
     )
     ");
 }
@@ -343,3 +484,946 @@ fn test_composition() {
     )
     "#);
 }
+
+#[test]
+fn test_take_str() {
+    let parser = take(3);
+    let result = parser.parse("héllo");
+    insta::assert_debug_snapshot!(result, @r#"
+    Ok(
+        (
+            "hél",
+            "lo",
+        ),
+    )
+    "#);
+}
+
+#[test]
+fn test_take_bytes() {
+    let parser = take(2);
+    let result = parser.parse(&[1u8, 2, 3][..]);
+    insta::assert_debug_snapshot!(result, @r"
+    Ok(
+        (
+            [
+                1,
+                2,
+            ],
+            [
+                3,
+            ],
+        ),
+    )
+    ");
+}
+
+#[test]
+fn test_take_not_enough_input() {
+    let parser = take(10);
+    let result = parser.parse("hello");
+    insta::assert_debug_snapshot!(result, @r"
+    Err(
+        Incomplete {
+            needed: Some(
+                5,
+            ),
+        },
+    )
+    ");
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Tok {
+    Plus,
+    Minus,
+    Num(i32),
+}
+
+#[test]
+fn test_one_of_slice() {
+    let parser = one_of_slice(&[Tok::Plus, Tok::Minus]);
+    let result = parser.parse(vec![Tok::Minus, Tok::Num(1)]);
+    insta::assert_debug_snapshot!(result, @r"
+    Ok(
+        (
+            Minus,
+            [
+                Num(
+                    1,
+                ),
+            ],
+        ),
+    )
+    ");
+}
+
+#[test]
+fn test_none_of_slice() {
+    let parser = none_of_slice(&[Tok::Plus, Tok::Minus]);
+    let result = parser.parse(vec![Tok::Num(1), Tok::Plus]);
+    insta::assert_debug_snapshot!(result, @r"
+    Ok(
+        (
+            Num(
+                1,
+            ),
+            [
+                Plus,
+            ],
+        ),
+    )
+    ");
+}
+
+#[test]
+fn test_rest_after_header() {
+    let parser = token('#').and(rest());
+    let result = parser.parse("#header body");
+    insta::assert_debug_snapshot!(result, @r#"
+    Ok(
+        (
+            (
+                '#',
+                "header body",
+            ),
+            "",
+        ),
+    )
+    "#);
+}
+
+#[test]
+fn test_rest_empty_input() {
+    let parser = rest();
+    let result = parser.parse(&b""[..]);
+    insta::assert_debug_snapshot!(result, @r"
+    Ok(
+        (
+            [],
+            [],
+        ),
+    )
+    ");
+}
+
+#[test]
+fn test_memoize_reuses_result() {
+    let memo = Memo::new();
+    let calls = std::cell::Cell::new(0);
+    let letter = memoize(
+        item().map(|c: char| {
+            calls.set(calls.get() + 1);
+            c
+        }),
+        &memo,
+    );
+    let parser = memo_scope(
+        (&letter).skip(token('x')).or((&letter).skip(token('y'))),
+        &memo,
+    );
+    let first = parser.parse("ay");
+    let second = parser.parse("by");
+    insta::assert_debug_snapshot!((first, second, calls.get()), @r#"
+    (
+        Ok(
+            (
+                'a',
+                "",
+            ),
+        ),
+        Ok(
+            (
+                'b',
+                "",
+            ),
+        ),
+        2,
+    )
+    "#);
+}
+
+#[test]
+fn test_memoize_outside_scope_does_not_cache() {
+    let memo = Memo::new();
+    let calls = std::cell::Cell::new(0);
+    let letter = memoize(
+        item().map(|c: char| {
+            calls.set(calls.get() + 1);
+            c
+        }),
+        &memo,
+    );
+    let first = letter.parse("ab");
+    let second = letter.parse("ab");
+    insta::assert_debug_snapshot!((first, second, calls.get()), @r#"
+    (
+        Ok(
+            (
+                'a',
+                "b",
+            ),
+        ),
+        Ok(
+            (
+                'a',
+                "b",
+            ),
+        ),
+        2,
+    )
+    "#);
+}
+
+#[test]
+fn test_spanned_records_offsets() {
+    let parser = token('a').skip(token(' ')).and(spanned(token('é').many1()));
+    let result = parser.parse(Located::new("a ééx"));
+    insta::assert_debug_snapshot!(result, @r#"
+    Ok(
+        (
+            (
+                'a',
+                (
+                    [
+                        'é',
+                        'é',
+                    ],
+                    Span {
+                        start: 2,
+                        end: 6,
+                    },
+                ),
+            ),
+            Located {
+                input: "x",
+                offset: 6,
+            },
+        ),
+    )
+    "#);
+}
+
+#[test]
+fn test_fold_sep_by_sum() {
+    let parser = fold_sep_by(unsigned(), char(','), 0, |acc, n| acc + n);
+    let result = parser.parse("1,2,3,;");
+    insta::assert_debug_snapshot!(result, @r#"
+    Ok(
+        (
+            6,
+            ",;",
+        ),
+    )
+    "#);
+}
+
+#[test]
+fn test_fold_sep_by_empty() {
+    let parser = fold_sep_by(unsigned(), char(','), 0, |acc, n| acc + n);
+    let result = parser.parse(";");
+    insta::assert_debug_snapshot!(result, @r#"
+    Ok(
+        (
+            0,
+            ";",
+        ),
+    )
+    "#);
+}
+
+#[test]
+fn test_fold_sep_by1_failure() {
+    let parser = fold_sep_by1(unsigned(), char(','), 0, |acc, n| acc + n);
+    let result = parser.parse(";");
+    insta::assert_debug_snapshot!(result, @r#"
+    Err(
+        Expected {
+            expected: "digit",
+            found: Some(
+                "';'",
+            ),
+            input: ";",
+        },
+    )
+    "#);
+}
+
+#[derive(Debug)]
+struct NotClone(char);
+
+fn unwrap_all(items: Vec<NotClone>) -> String {
+    items.into_iter().map(|NotClone(c)| c).collect()
+}
+
+#[test]
+fn test_many_without_clone() {
+    let parser = token('x').map(NotClone).many1().map(unwrap_all);
+    let result = parser.parse("xxy");
+    insta::assert_debug_snapshot!(result, @r#"
+    Ok(
+        (
+            "xx",
+            "y",
+        ),
+    )
+    "#);
+}
+
+#[test]
+fn test_sep_by_without_clone() {
+    let parser = sep_by(item().map(NotClone), token(',')).map(unwrap_all);
+    let result = parser.parse("a,b");
+    insta::assert_debug_snapshot!(result, @r#"
+    Ok(
+        (
+            "ab",
+            "",
+        ),
+    )
+    "#);
+}
+
+#[test]
+fn test_choice_all_fail() {
+    let parser = choice(vec![token('a'), token('b')]);
+    let result = parser.parse("c");
+    insta::assert_debug_snapshot!(result, @r#"
+    Err(
+        Many(
+            [
+                Expected {
+                    expected: "'a'",
+                    found: Some(
+                        "'c'",
+                    ),
+                    input: "c",
+                },
+                Expected {
+                    expected: "'b'",
+                    found: Some(
+                        "'c'",
+                    ),
+                    input: "c",
+                },
+            ],
+        ),
+    )
+    "#);
+}
+
+#[test]
+fn test_sep_by_trailing_separator_backtracks() {
+    let parser = sep_by(item(), token(','));
+    let result = parser.parse(vec!['a', ',', 'b', ',']);
+    insta::assert_debug_snapshot!(result, @r"
+    Ok(
+        (
+            [
+                'a',
+                'b',
+            ],
+            [
+                ',',
+            ],
+        ),
+    )
+    ");
+}
+
+#[test]
+fn test_ops_alternation() {
+    let parser = token('a').ops() | token('b') | token('c');
+    let result = parser.many().parse(vec!['c', 'a', 'b', 'd']);
+    insta::assert_debug_snapshot!(result, @r"
+    Ok(
+        (
+            [
+                'c',
+                'a',
+                'b',
+            ],
+            [
+                'd',
+            ],
+        ),
+    )
+    ");
+}
+
+#[test]
+fn test_ops_sequencing() {
+    let parser = token('(').ops() >> (item().ops() + item().ops()) << token(')').ops();
+    let result = parser.parse(vec!['(', 'x', 'y', ')']);
+    insta::assert_debug_snapshot!(result, @r"
+    Ok(
+        (
+            (
+                'x',
+                'y',
+            ),
+            [],
+        ),
+    )
+    ");
+}
+
+#[test]
+fn test_ops_precedence() {
+    // `>>` binds tighter than `|`, so this is `(a >> b) | c`
+    let parser = token('a').ops() >> token('b').ops() | token('c');
+    let result = parser.parse(vec!['c']);
+    insta::assert_debug_snapshot!(result, @r"
+    Ok(
+        (
+            'c',
+            [],
+        ),
+    )
+    ");
+}
+
+#[test]
+fn test_trace_is_transparent() {
+    let parser = token('a').trace("a").many().trace("many a");
+    let result = parser.parse(vec!['a', 'a', 'b']);
+    insta::assert_debug_snapshot!(result, @r"
+    Ok(
+        (
+            [
+                'a',
+                'a',
+            ],
+            [
+                'b',
+            ],
+        ),
+    )
+    ");
+}
+
+#[test]
+fn test_by_ref_reuses_parser() {
+    let letter = satisfy(|c: &char| c.is_alphabetic());
+    let word = letter.by_ref().many1();
+    let initial = letter.by_ref().skip(token('.'));
+    let parser = initial.skip(token(' ')).and(word);
+    let result = parser.parse(vec!['J', '.', ' ', 'D', 'o', 'e']);
+    insta::assert_debug_snapshot!(result, @r"
+    Ok(
+        (
+            (
+                'J',
+                [
+                    'D',
+                    'o',
+                    'e',
+                ],
+            ),
+            [],
+        ),
+    )
+    ");
+}
+
+#[test]
+fn test_count_of() {
+    let parser = count_of(token('a'));
+    let result = parser.parse(vec!['a', 'a', 'a', 'b']);
+    insta::assert_debug_snapshot!(result, @r"
+    Ok(
+        (
+            3,
+            [
+                'b',
+            ],
+        ),
+    )
+    ");
+}
+
+#[test]
+fn test_count_of_zero() {
+    let parser = count_of(token('a'));
+    let result = parser.parse(vec!['b']);
+    insta::assert_debug_snapshot!(result, @r"
+    Ok(
+        (
+            0,
+            [
+                'b',
+            ],
+        ),
+    )
+    ");
+}
+
+#[test]
+fn test_count_of1_fails_on_zero() {
+    let parser = count_of1(token('a'));
+    let result = parser.parse(vec!['b']);
+    insta::assert_debug_snapshot!(result, @r#"
+    Err(
+        Expected {
+            expected: "'a'",
+            found: Some(
+                "'b'",
+            ),
+            input: [
+                'b',
+            ],
+        },
+    )
+    "#);
+}
+
+#[test]
+fn test_map_with_input_on_vec() {
+    let parser = token('a')
+        .many()
+        .map_with_input(|items, consumed: Vec<char>| (items.len(), consumed));
+    let result = parser.parse(vec!['a', 'a', 'b']);
+    insta::assert_debug_snapshot!(result, @r"
+    Ok(
+        (
+            (
+                2,
+                [
+                    'a',
+                    'a',
+                ],
+            ),
+            [
+                'b',
+            ],
+        ),
+    )
+    ");
+}
+
+#[test]
+fn test_repeat_until_eof() {
+    let parser = repeat_until_eof(terminated(digit().many1(), char(',').optional()));
+    let result = parser.parse("1,23,4");
+    insta::assert_debug_snapshot!(result, @r#"
+    Ok(
+        (
+            [
+                [
+                    '1',
+                ],
+                [
+                    '2',
+                    '3',
+                ],
+                [
+                    '4',
+                ],
+            ],
+            "",
+        ),
+    )
+    "#);
+}
+
+#[test]
+fn test_repeat_until_eof_no_progress() {
+    let parser = repeat_until_eof(digit().many());
+    let result = parser.parse("12ab");
+    insta::assert_debug_snapshot!(result, @r#"
+    Err(
+        Message {
+            message: "parser made no progress",
+            input: "ab",
+        },
+    )
+    "#);
+}
+
+#[test]
+fn test_many_stops_on_zero_width_success() {
+    let parser = char('a').optional().many();
+    let result = parser.parse("aab");
+    insta::assert_debug_snapshot!(result, @r#"
+    Ok(
+        (
+            [
+                Some(
+                    'a',
+                ),
+                Some(
+                    'a',
+                ),
+            ],
+            "b",
+        ),
+    )
+    "#);
+}
+
+#[test]
+fn test_count_of_zero_width_parser() {
+    let parser = count_of(spaces());
+    let result = parser.parse("x");
+    insta::assert_debug_snapshot!(result, @r#"
+    Ok(
+        (
+            0,
+            "x",
+        ),
+    )
+    "#);
+}
+
+#[test]
+fn test_delimited_list() {
+    let parser = delimited_list(char('['), digit(), char(','), char(']'));
+    let results = (parser.parse("[]"), parser.parse("[1,2,3]!"));
+    insta::assert_debug_snapshot!(results, @r#"
+    (
+        Ok(
+            (
+                [],
+                "",
+            ),
+        ),
+        Ok(
+            (
+                [
+                    '1',
+                    '2',
+                    '3',
+                ],
+                "!",
+            ),
+        ),
+    )
+    "#);
+}
+
+#[test]
+fn test_delimited_list_trailing_separator() {
+    let strict = delimited_list(char('['), digit(), char(','), char(']'));
+    let lenient = delimited_list(char('['), digit(), char(','), char(']')).allow_trailing_sep();
+    let results = (strict.parse("[1,2,]"), lenient.parse("[1,2,]"));
+    insta::assert_debug_snapshot!(results, @r#"
+    (
+        Err(
+            Expected {
+                expected: "digit",
+                found: Some(
+                    "']'",
+                ),
+                input: "]",
+            },
+        ),
+        Ok(
+            (
+                [
+                    '1',
+                    '2',
+                ],
+                "",
+            ),
+        ),
+    )
+    "#);
+}
+
+#[test]
+fn test_delimited_list_errors() {
+    let parser = delimited_list(char('['), digit(), char(','), char(']'));
+    let bad_item = parser.parse("[1,x]").unwrap_err();
+    let unclosed = parser.parse("[1,2 3]").unwrap_err();
+    insta::assert_snapshot!(format!("{}\n{}", bad_item, unclosed), @r#"
+    expected digit, found 'x' at "x]"
+    missing closing delimiter: expected ']', found ' ' at " 3]"
+    "#);
+}
+
+#[test]
+fn test_many_filter_map() {
+    let parser = many_filter_map(alphanumeric(), |c: char| c.to_digit(10));
+    let result = parser.parse("a1b2c3!");
+    insta::assert_debug_snapshot!(result, @r#"
+    Ok(
+        (
+            [
+                1,
+                2,
+                3,
+            ],
+            "!",
+        ),
+    )
+    "#);
+}
+
+/// Recursive grammar of nested brackets, returning the nesting depth
+struct Brackets {
+    limit: DepthLimit,
+}
+
+impl<'a> Parser<&'a str, usize> for Brackets {
+    fn parse(&self, input: &'a str) -> ParseResult<&'a str, usize> {
+        let empty = peek(char(']')).map(|_| 0);
+        let inner = empty.or(nested(self, &self.limit));
+        delimited(char('['), inner, char(']'))
+            .map(|depth| depth + 1)
+            .parse(input)
+    }
+}
+
+#[test]
+fn test_nested_within_limit() {
+    let parser = Brackets {
+        limit: DepthLimit::new(4),
+    };
+    let result = parser.parse("[[[]]]");
+    insta::assert_debug_snapshot!((result, parser.limit.depth()), @r#"
+    (
+        Ok(
+            (
+                3,
+                "",
+            ),
+        ),
+        0,
+    )
+    "#);
+}
+
+#[test]
+fn test_nested_exceeds_limit() {
+    let parser = Brackets {
+        limit: DepthLimit::new(2),
+    };
+    let input = "[".repeat(10_000) + &"]".repeat(10_000);
+    let error = parser.parse(&input).unwrap_err();
+    insta::assert_snapshot!(error.to_string(), @r#"multiple errors: expected ']', found '[' at "[[[[[[[[[[[[[[[[[[[[...; multiple errors: expected ']', found '[' at "[[[[[[[[[[[[[[[[[[[[...; multiple errors: expected ']', found '[' at "[[[[[[[[[[[[[[[[[[[[...; maximum nesting depth exceeded at "[[[[[[[[[[[[[[[[[[[[..."#);
+}
+
+#[test]
+fn test_tokens_on_token_stream() {
+    let parser = tokens(&["order", "by"]);
+    let result = parser.parse(vec!["order", "by", "name"]);
+    insta::assert_debug_snapshot!(result, @r#"
+    Ok(
+        (
+            [
+                "order",
+                "by",
+            ],
+            [
+                "name",
+            ],
+        ),
+    )
+    "#);
+}
+
+#[test]
+fn test_tokens_reports_diverging_position() {
+    let parser = tokens(&["group", "by"]);
+    let mismatch = parser.parse(vec!["group", "of", "name"]).unwrap_err();
+    let short = parser.parse(vec!["group"]).unwrap_err();
+    insta::assert_snapshot!(format!("{}\n{}", mismatch, short), @r#"
+    expected "by" at position 1 of ["group", "by"], found "of" at ["group", "of", "nam...
+    incomplete input, needed 1 more
+    "#);
+}
+
+#[test]
+fn test_inspect_counts_tokens() {
+    let count = std::cell::Cell::new(0);
+    let word = identifier().inspect(|_| count.set(count.get() + 1));
+    let parser = sep_by(word, char(' '));
+    let result = parser.parse("one two three!");
+    insta::assert_debug_snapshot!((result, count.get()), @r#"
+    (
+        Ok(
+            (
+                [
+                    "one",
+                    "two",
+                    "three",
+                ],
+                "!",
+            ),
+        ),
+        3,
+    )
+    "#);
+}
+
+#[test]
+fn test_sep_by_collect_into_map() {
+    let pair = identifier().skip(char('=')).and(unsigned());
+    let parser = sep_by_collect(pair, char(';'));
+    let result: ParseResult<&str, std::collections::BTreeMap<_, _>> = parser.parse("b=2;a=1;c=3;");
+    insta::assert_debug_snapshot!(result, @r#"
+    Ok(
+        (
+            {
+                "a": 1,
+                "b": 2,
+                "c": 3,
+            },
+            ";",
+        ),
+    )
+    "#);
+}
+
+#[test]
+fn test_sep_by_collect_into_set() {
+    let parser = sep_by_collect(digit(), char(','));
+    let result: ParseResult<&str, std::collections::BTreeSet<char>> = parser.parse("3,1,3,2");
+    insta::assert_debug_snapshot!(result, @r#"
+    Ok(
+        (
+            {
+                '1',
+                '2',
+                '3',
+            },
+            "",
+        ),
+    )
+    "#);
+}
+
+#[test]
+fn test_sequence() {
+    let parser = sequence("key".chars().map(char).collect());
+    let results = (parser.parse("keys"), parser.parse("kex"));
+    insta::assert_debug_snapshot!(results, @r#"
+    (
+        Ok(
+            (
+                [
+                    'k',
+                    'e',
+                    'y',
+                ],
+                "s",
+            ),
+        ),
+        Err(
+            Expected {
+                expected: "'y'",
+                found: Some(
+                    "'x'",
+                ),
+                input: "x",
+            },
+        ),
+    )
+    "#);
+}
+
+#[test]
+fn test_backtracking_clones_input_only_into_errors() {
+    // An input that counts how often it is cloned
+    #[derive(Debug)]
+    struct Counted {
+        chars: std::rc::Rc<[char]>,
+        index: usize,
+        clones: std::rc::Rc<std::cell::Cell<usize>>,
+    }
+
+    impl Clone for Counted {
+        fn clone(&self) -> Self {
+            self.clones.set(self.clones.get() + 1);
+            Counted {
+                chars: self.chars.clone(),
+                index: self.index,
+                clones: self.clones.clone(),
+            }
+        }
+    }
+
+    impl Input for Counted {
+        type Item = char;
+
+        fn uncons(&self) -> Option<(Self::Item, Self)> {
+            let c = *self.chars.get(self.index)?;
+            let rest = Counted {
+                chars: self.chars.clone(),
+                index: self.index + 1,
+                clones: self.clones.clone(),
+            };
+            Some((c, rest))
+        }
+
+        fn len(&self) -> Option<usize> {
+            Some(self.chars.len() - self.index)
+        }
+
+        fn same_position(&self, other: &Self) -> bool {
+            self.index == other.index
+        }
+    }
+
+    let clones = std::rc::Rc::new(std::cell::Cell::new(0));
+    let input = Counted {
+        chars: "abba".chars().collect(),
+        index: 0,
+        clones: clones.clone(),
+    };
+    // Only the two failed `token('a')` attempts copy the input, into their errors
+    let parser = token('a').or(token('b')).optional().many();
+    let (result, _) = parser.parse(input).unwrap();
+    insta::assert_debug_snapshot!((result, clones.get()), @r"
+    (
+        [
+            Some(
+                'a',
+            ),
+            Some(
+                'b',
+            ),
+            Some(
+                'b',
+            ),
+            Some(
+                'a',
+            ),
+        ],
+        2,
+    )
+    ");
+}
+
+#[test]
+fn test_or_else_chooses_fallback_from_error() {
+    // Only retry leniently when the strict parser rejected leading zeros
+    let parser = json_number().or_else(|error: &ParseError<&str>| {
+        let leading_zeros = matches!(
+            error,
+            ParseError::Message { message, .. } if message.starts_with("leading zeros")
+        );
+        json_number_opts(JsonNumberOptions {
+            allow_leading_zeros: leading_zeros,
+        })
+    });
+    let results = (
+        parser.parse("007"),
+        parser.parse("-x").map_err(|e| e.to_string()),
+    );
+    insta::assert_debug_snapshot!(results, @r#"
+    (
+        Ok(
+            (
+                Int(
+                    7,
+                ),
+                "",
+            ),
+        ),
+        Err(
+            "multiple errors: expected number, found character 'x' at \"-x\"; expected number, found character 'x' at \"-x\"",
+        ),
+    )
+    "#);
+}
@@ -0,0 +1,53 @@
+//! Rendered error report tests using snapshot testing
+#![cfg(feature = "diagnostics")]
+
+use simons_parser::*;
+
+#[test]
+fn test_report_expected() {
+    let source = "let x = ?;";
+    let parser = string("let x = ").and(digit());
+    let error = parser.parse(source).unwrap_err();
+    insta::assert_snapshot!(report(&error, source), @r"
+    Error: parse error
+       ╭─[ <unknown>:1:9 ]
+       │
+     1 │ let x = ?;
+       │         ┬  
+       │         ╰── expected digit, found '?'
+    ───╯
+    ");
+}
+
+#[test]
+fn test_report_many_with_context() {
+    let source = "key: ?";
+    let parser = string("key: ").and(char('a').or(char('b')).with_context("value"));
+    let error = parser.parse(source).unwrap_err();
+    insta::assert_snapshot!(report(&error, source), @r"
+    Error: parse error
+       ╭─[ <unknown>:1:6 ]
+       │
+     1 │ key: ?
+       │      ┬  
+       │      ╰── value: expected 'a', found '?'
+       │      │  
+       │      ╰── value: expected 'b', found '?'
+    ───╯
+    ");
+}
+
+#[test]
+fn test_report_end_of_input() {
+    let source = "ab";
+    let error = complete(string("abc")).parse(source).unwrap_err();
+    insta::assert_snapshot!(report(&error, source), @r"
+    Error: parse error
+       ╭─[ <unknown>:1:3 ]
+       │
+     1 │ ab
+       │   │ 
+       │   ╰─ unexpected end of input
+    ───╯
+    ");
+}
@@ -0,0 +1,443 @@
+//! Error construction and reporting tests using snapshot testing
+
+use simons_parser::*;
+
+#[test]
+fn test_with_context_wraps_error() {
+    let parser = digit().with_context("while parsing the date field");
+    let result = parser.parse("x");
+    insta::assert_debug_snapshot!(result, @r#"
+    Err(
+        Context {
+            context: "while parsing the date field",
+            error: Expected {
+                expected: "digit",
+                found: Some(
+                    "'x'",
+                ),
+                input: "x",
+            },
+        },
+    )
+    "#);
+}
+
+#[test]
+fn test_context_display() {
+    let parser = char('-').with_context("while parsing the date field");
+    let error = parser.parse("x").unwrap_err();
+    insta::assert_snapshot!(error.to_string(), @r#"while parsing the date field: expected '-', found 'x' at "x""#);
+}
+
+#[test]
+fn test_map_err_replaces_error() {
+    let parser = digit().map_err(|error| match error {
+        ParseError::Incomplete { .. } => ParseError::message("date ended early", ""),
+        other => other,
+    });
+    let result = parser.parse("");
+    insta::assert_debug_snapshot!(result, @r#"
+    Err(
+        Message {
+            message: "date ended early",
+            input: "",
+        },
+    )
+    "#);
+}
+
+#[test]
+fn test_string_incomplete() {
+    let parser = string("hello");
+    let result = parser.parse("hel");
+    insta::assert_debug_snapshot!(result, @r"
+    Err(
+        Incomplete {
+            needed: Some(
+                2,
+            ),
+        },
+    )
+    ");
+}
+
+#[test]
+fn test_complete_converts_incomplete() {
+    let parser = complete(string("hello"));
+    let result = parser.parse("hel");
+    insta::assert_debug_snapshot!(result, @r"
+    Err(
+        UnexpectedEof,
+    )
+    ");
+}
+
+#[test]
+fn test_complete_converts_nested_incomplete() {
+    let parser = complete(char('a').or(char('b')));
+    let result = parser.parse("");
+    insta::assert_debug_snapshot!(result, @r"
+    Err(
+        Many(
+            [
+                UnexpectedEof,
+                UnexpectedEof,
+            ],
+        ),
+    )
+    ");
+}
+
+#[test]
+fn test_complete_method_reuses_streaming_parser() {
+    let streaming = string("let").skip(spaces1());
+    let batch = string("let").skip(spaces1()).complete();
+    insta::assert_debug_snapshot!((streaming.parse("let"), batch.parse("let")), @r"
+    (
+        Err(
+            Incomplete {
+                needed: Some(
+                    1,
+                ),
+            },
+        ),
+        Err(
+            UnexpectedEof,
+        ),
+    )
+    ");
+}
+
+#[test]
+fn test_incomplete_display() {
+    let error = take(4).parse(&b"ab"[..]).unwrap_err();
+    insta::assert_snapshot!(error.to_string(), @r"incomplete input, needed 2 more");
+}
+
+#[test]
+fn test_display_truncates_long_input() {
+    let input = "x".repeat(10_000);
+    let error = char('a').parse(input.as_str()).unwrap_err();
+    insta::assert_snapshot!(error.to_string(), @r#"expected 'a', found 'x' at "xxxxxxxxxxxxxxxxxxxx..."#);
+}
+
+#[test]
+fn test_display_never_cuts_an_escape() {
+    let input = format!("{}\u{7}\n{}", "x".repeat(19), "y".repeat(30));
+    let error = char('a').parse(input.as_str()).unwrap_err();
+    insta::assert_snapshot!(error.to_string(), @r#"expected 'a', found 'x' at "xxxxxxxxxxxxxxxxxxx\u{7}..."#);
+}
+
+#[test]
+fn test_display_truncates_on_char_boundary() {
+    let error = string("abc").parse("ééééééééééééééééééééééé").unwrap_err();
+    insta::assert_snapshot!(error.to_string(), @r#"expected string 'abc', found character 'é' at "éééééééééééééééééééé..."#);
+}
+
+#[test]
+fn test_display_short_input_untruncated() {
+    let error = ParseError::message("bad", &[1u8, 2, 3][..]);
+    insta::assert_snapshot!(error.to_string(), @r"bad at [1, 2, 3]");
+}
+
+#[test]
+fn test_error_source_chain() {
+    use std::error::Error;
+
+    let parser = char('a').or(char('b')).with_context("letter");
+    let error = parser.parse("c").unwrap_err();
+    let mut chain = vec![error.to_string()];
+    let mut source = error.source();
+    while let Some(cause) = source {
+        chain.push(cause.to_string());
+        source = cause.source();
+    }
+    insta::assert_debug_snapshot!(chain, @r#"
+    [
+        "letter: multiple errors: expected 'a', found 'c' at \"c\"; expected 'b', found 'c' at \"c\"",
+        "multiple errors: expected 'a', found 'c' at \"c\"; expected 'b', found 'c' at \"c\"",
+        "expected 'a', found 'c' at \"c\"",
+    ]
+    "#);
+}
+
+#[test]
+fn test_error_source_none_for_leaf() {
+    use std::error::Error;
+
+    let error = char('a').parse("c").unwrap_err();
+    insta::assert_debug_snapshot!(error.source().is_none(), @r"true");
+}
+
+#[test]
+fn test_into_io_error() {
+    fn read_digit(source: &str) -> std::io::Result<char> {
+        Ok(parse_complete(&digit(), source)?)
+    }
+
+    let error = read_digit("x").unwrap_err();
+    insta::assert_debug_snapshot!((error.kind(), error.to_string()), @r#"
+    (
+        InvalidData,
+        "expected digit, found 'x' at \"x\"",
+    )
+    "#);
+}
+
+#[test]
+fn test_recover_collects_all_errors() {
+    let (result, errors) = parse_with_diagnostics(
+        |diagnostics| {
+            let statement = identifier().skip(char('=')).and(unsigned()).skip(char(';'));
+            recover(statement, char(';'), diagnostics).many()
+        },
+        "a=1;b=?;c=3;=4;",
+    );
+    insta::assert_debug_snapshot!((result, errors), @r#"
+    (
+        Ok(
+            (
+                [
+                    Some(
+                        (
+                            "a",
+                            1,
+                        ),
+                    ),
+                    None,
+                    Some(
+                        (
+                            "c",
+                            3,
+                        ),
+                    ),
+                    None,
+                ],
+                "",
+            ),
+        ),
+        [
+            Expected {
+                expected: "digit",
+                found: Some(
+                    "'?'",
+                ),
+                input: "?;c=3;=4;",
+            },
+            Expected {
+                expected: "identifier",
+                found: Some(
+                    "character '='",
+                ),
+                input: "=4;",
+            },
+        ],
+    )
+    "#);
+}
+
+#[test]
+fn test_recover_skips_to_end_without_sync() {
+    let (result, errors) = parse_with_diagnostics(
+        |diagnostics| recover(digit(), char(';'), diagnostics),
+        "abc",
+    );
+    insta::assert_debug_snapshot!((result, errors.len()), @r#"
+    (
+        Ok(
+            (
+                None,
+                "",
+            ),
+        ),
+        1,
+    )
+    "#);
+}
+
+#[test]
+fn test_recover_zero_width_sync_still_advances() {
+    // `sync` matches without consuming right where "2x" fails
+    let (result, errors) = parse_with_diagnostics(
+        |diagnostics| recover(digit().skip(char(',')), peek(digit()), diagnostics).many(),
+        "1,2x,3,",
+    );
+    insta::assert_debug_snapshot!((result, errors.len()), @r#"
+    (
+        Ok(
+            (
+                [
+                    Some(
+                        '1',
+                    ),
+                    None,
+                    Some(
+                        '3',
+                    ),
+                ],
+                "",
+            ),
+        ),
+        1,
+    )
+    "#);
+}
+
+#[test]
+fn test_recover_with_substitutes_default() {
+    let field = recover_with(integer().skip(char(',')), 0, char(','));
+    let result = field.many().parse("1,x?,3,-4,");
+    insta::assert_debug_snapshot!(result, @r#"
+    Ok(
+        (
+            [
+                1,
+                0,
+                3,
+                -4,
+            ],
+            "",
+        ),
+    )
+    "#);
+}
+
+#[test]
+fn test_named_error_chain_display() {
+    let number = complete(digit().named("digit").many1()).named("number");
+    let error = number.parse("").unwrap_err();
+    insta::assert_snapshot!(error.to_string(), @r"in number > digit: unexpected end of input");
+}
+
+#[test]
+fn test_named_error_structure() {
+    let parser = char('a').named("a").or(char('b')).named("letter");
+    let result = parser.parse("c");
+    insta::assert_debug_snapshot!(result, @r#"
+    Err(
+        Named {
+            names: [
+                "letter",
+            ],
+            error: Many(
+                [
+                    Named {
+                        names: [
+                            "a",
+                        ],
+                        error: Expected {
+                            expected: "'a'",
+                            found: Some(
+                                "'c'",
+                            ),
+                            input: "c",
+                        },
+                    },
+                    Expected {
+                        expected: "'b'",
+                        found: Some(
+                            "'c'",
+                        ),
+                        input: "c",
+                    },
+                ],
+            ),
+        },
+    )
+    "#);
+}
+
+#[test]
+fn test_expect_replaces_consuming_failure() {
+    let parser = digit()
+        .many1()
+        .and(char(';'))
+        .expect("expected a statement");
+    let result = parser.parse("12x");
+    insta::assert_debug_snapshot!(result, @r#"
+    Err(
+        Message {
+            message: "expected a statement",
+            input: "12x",
+        },
+    )
+    "#);
+}
+
+#[test]
+fn test_furthest_picks_deepest_alternative() {
+    let parser = string("let ").skip(digit()).or(string("letter"));
+    let error = parser.parse("let x").unwrap_err();
+    insta::assert_debug_snapshot!(error.furthest(), @r#"
+    Expected {
+        expected: "digit",
+        found: Some(
+            "'x'",
+        ),
+        input: "x",
+    }
+    "#);
+}
+
+#[test]
+fn test_furthest_error_report_points_at_line_and_column() {
+    let source = "[1, 2]\n[3, x]";
+    let row = || between(char('['), sep_by(integer(), string(", ")), char(']'));
+    let parser = row().skip(newline()).and(row());
+    let error = parser.parse(source).unwrap_err();
+    insta::assert_snapshot!(furthest_error_report(&error, source), @r#"
+    error at line 2, column 3: expected ']', found ',' at ", x]"
+      2 | [3, x]
+        |   ^
+    "#);
+}
+
+#[test]
+fn test_furthest_error_report_outside_source() {
+    // Counting back from the end of the source, the frame "x" lands inside the final 'é'
+    let source = "é 1xé";
+    let parser = string("é ").and(length_value(unsigned(), digit().many1()));
+    let error = parser.parse(source).unwrap_err();
+    insta::assert_snapshot!(furthest_error_report(&error, source), @r#"error: expected digit, found 'x' at "x""#);
+}
+
+#[test]
+fn test_error_inspectors() {
+    let parser = string("let ")
+        .skip(digit().named("digit"))
+        .or(string("letter"))
+        .with_context("in declaration");
+    let error = parser.parse("let x").unwrap_err();
+    let eof = complete(digit()).parse("").unwrap_err();
+    let incomplete = string("hello").or(string("help")).parse("he").unwrap_err();
+    let flags = [
+        (error.is_eof(), error.is_incomplete()),
+        (eof.is_eof(), eof.is_incomplete()),
+        (incomplete.is_eof(), incomplete.is_incomplete()),
+    ];
+    insta::assert_debug_snapshot!((error.messages(), error.furthest_input(), flags), @r#"
+    (
+        [
+            "in declaration: in digit: expected digit, found 'x'",
+            "in declaration: expected string 'letter', found character ' '",
+        ],
+        Some(
+            "x",
+        ),
+        [
+            (
+                false,
+                false,
+            ),
+            (
+                true,
+                false,
+            ),
+            (
+                false,
+                true,
+            ),
+        ],
+    )
+    "#);
+}
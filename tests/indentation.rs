@@ -0,0 +1,123 @@
+//! Indentation-sensitive parser tests using snapshot testing
+
+use simons_parser::*;
+
+/// A word of letters over `StrInput`
+fn word<'a>() -> impl Parser<StrInput<'a>, String> {
+    satisfy(|c: &char| c.is_alphabetic())
+        .many1()
+        .map(|chars| chars.into_iter().collect())
+}
+
+/// An outline entry: a word, optionally followed by an indented block of words
+fn entry<'a>() -> impl Parser<StrInput<'a>, (String, Option<Vec<String>>)> {
+    word().and(indented(block(word())).optional())
+}
+
+/// Summarize a result as the parsed value or the error message with its position
+fn describe<T>(result: ParseResult<StrInput<'_>, T>) -> Result<T, (String, usize, usize)> {
+    result.map(|(value, _)| value).map_err(|error| match error {
+        ParseError::Message { message, input } => (message, input.line(), input.column()),
+        other => (format!("{:?}", other), 0, 0),
+    })
+}
+
+#[test]
+fn test_block_of_indented_blocks() {
+    let parser = block(entry());
+    let result = parser.parse(StrInput::new("fruit\n  apple\n  pear\nveg\n  leek\n"));
+    insta::assert_debug_snapshot!(describe(result), @r#"
+    Ok(
+        [
+            (
+                "fruit",
+                Some(
+                    [
+                        "apple",
+                        "pear",
+                    ],
+                ),
+            ),
+            (
+                "veg",
+                Some(
+                    [
+                        "leek",
+                    ],
+                ),
+            ),
+        ],
+    )
+    "#);
+}
+
+#[test]
+fn test_block_items_consuming_their_newline() {
+    let parser = block(word().skip(token('\n')));
+    let result = parser.parse(StrInput::new("apple\npear\nplum\n"));
+    insta::assert_debug_snapshot!(describe(result), @r#"
+    Ok(
+        [
+            "apple",
+            "pear",
+            "plum",
+        ],
+    )
+    "#);
+}
+
+#[test]
+fn test_block_rejects_unexpected_indentation() {
+    let parser = word().and(indented(block(word())));
+    let result = parser.parse(StrInput::new("fruit\n  apple\n    pear\n"));
+    insta::assert_debug_snapshot!(describe(result), @r#"
+    Err(
+        (
+            "unexpected indentation",
+            3,
+            5,
+        ),
+    )
+    "#);
+}
+
+#[test]
+fn test_indented_requires_deeper_column() {
+    let parser = same_line(word()).and(indented(word()));
+    let result = parser.parse(StrInput::new("  key\n  value"));
+    insta::assert_debug_snapshot!(describe(result), @r#"
+    Err(
+        (
+            "expected indentation beyond column 3",
+            2,
+            3,
+        ),
+    )
+    "#);
+}
+
+#[test]
+fn test_same_line() {
+    let parser = word().and(same_line(word()));
+    let results = (
+        describe(parser.parse(StrInput::new("key \tvalue"))),
+        describe(parser.parse(StrInput::new("key\nvalue"))),
+    );
+    insta::assert_debug_snapshot!(results, @r#"
+    (
+        Ok(
+            (
+                "key",
+                "value",
+            ),
+        ),
+        Err(
+            (
+                "expected more on the same line",
+                1,
+                4,
+            ),
+        ),
+    )
+    "#);
+}
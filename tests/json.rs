@@ -0,0 +1,153 @@
+//! Reference JSON parser tests using snapshot testing
+#![cfg(feature = "json")]
+
+use simons_parser::Parser;
+use simons_parser::formats::json;
+
+#[test]
+fn test_json_document() {
+    let source = r#" { "name": "simon", "tags": ["a", "b"], "age": 30, "score": -1.5e2, "ok": true, "none": null } "#;
+    let result = json::parse(source);
+    insta::assert_debug_snapshot!(result, @r#"
+    Ok(
+        Object(
+            [
+                (
+                    "name",
+                    String(
+                        "simon",
+                    ),
+                ),
+                (
+                    "tags",
+                    Array(
+                        [
+                            String(
+                                "a",
+                            ),
+                            String(
+                                "b",
+                            ),
+                        ],
+                    ),
+                ),
+                (
+                    "age",
+                    Number(
+                        Int(
+                            30,
+                        ),
+                    ),
+                ),
+                (
+                    "score",
+                    Number(
+                        Float(
+                            -150.0,
+                        ),
+                    ),
+                ),
+                (
+                    "ok",
+                    Bool(
+                        true,
+                    ),
+                ),
+                (
+                    "none",
+                    Null,
+                ),
+            ],
+        ),
+    )
+    "#);
+}
+
+#[test]
+fn test_json_string_escapes() {
+    let result = json::parse(r#""tab\there \"q\" é 😀 \/""#);
+    insta::assert_debug_snapshot!(result, @r#"
+    Ok(
+        String(
+            "tab\there \"q\" é 😀 /",
+        ),
+    )
+    "#);
+}
+
+#[test]
+fn test_json_round_trip() {
+    let documents = [
+        r#"{"a":[1,2.5,{"b":null}],"c":"line\nbreak","d":{}}"#,
+        r#"[[],[[]],true,false,-0,1e-7,"\u0001"]"#,
+        r#"{"dup":1,"dup":2,"unicode":"é😀"}"#,
+    ];
+    let round_trips: Vec<_> = documents
+        .into_iter()
+        .map(|source| {
+            let value = json::parse(source).unwrap();
+            let printed = value.to_string();
+            let reparsed = json::parse(&printed).unwrap();
+            (printed, reparsed == value)
+        })
+        .collect();
+    insta::assert_debug_snapshot!(round_trips, @r#"
+    [
+        (
+            "{\"a\":[1,2.5,{\"b\":null}],\"c\":\"line\\nbreak\",\"d\":{}}",
+            true,
+        ),
+        (
+            "[[],[[]],true,false,0,1e-7,\"\\u0001\"]",
+            true,
+        ),
+        (
+            "{\"dup\":1,\"dup\":2,\"unicode\":\"é😀\"}",
+            true,
+        ),
+    ]
+    "#);
+}
+
+#[test]
+fn test_json_errors() {
+    let errors: Vec<_> = [r#"{"a" 1}"#, "[1, 2", r#""\ud800""#, "[01]", "[1,]"]
+        .into_iter()
+        .map(|source| json::parse(source).unwrap_err().to_string())
+        .collect();
+    insta::assert_debug_snapshot!(errors, @r#"
+    [
+        "expected ':', found '1' at \"1}\"",
+        "missing closing delimiter: unexpected end of input",
+        "unpaired surrogate 0xd800 at \"\\\\ud800\\\"\"",
+        "leading zeros are not allowed at \"01]\"",
+        "expected JSON value, found character ']' at \"]\"",
+    ]
+    "#);
+}
+
+#[test]
+fn test_json_depth_limit() {
+    let deep = "[".repeat(100_000);
+    let shallow = format!("{}{}", "[".repeat(3), "]".repeat(3));
+    let limited = json::value().max_depth(2).parse(&shallow);
+    insta::assert_debug_snapshot!((
+        json::parse(&deep).unwrap_err().to_string(),
+        limited.unwrap_err().to_string(),
+    ), @r#"
+    (
+        "maximum nesting depth exceeded at \"[[[[[[[[[[[[[[[[[[[[...",
+        "maximum nesting depth exceeded at \"[]]]\"",
+    )
+    "#);
+}
+
+#[test]
+fn test_json_empty_containers() {
+    let result = json::parse("{ \"a\" : [ ] , \"b\" : { } }").map(|value| value.to_string());
+    insta::assert_debug_snapshot!(result, @r#"
+    Ok(
+        "{\"a\":[],\"b\":{}}",
+    )
+    "#);
+}
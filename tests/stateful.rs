@@ -0,0 +1,121 @@
+//! Stateful parser tests using snapshot testing
+
+use simons_parser::stateful::*;
+use simons_parser::*;
+
+/// One line of an indentation-sensitive outline, with the indentation stack as state
+///
+/// Yields the nesting depth of the line together with its text.
+fn outline_line<'a>() -> impl StatefulParser<Vec<usize>, &'a str, (usize, &'a str)> {
+    lift(take_while(|c: &char| *c == ' ').and(take_while1(|c: &char| *c != '\n')))
+        .and(lift(newline().optional()))
+        .with_state(|stack: &mut Vec<usize>, ((indent, text), _)| {
+            let indent = indent.len();
+            let mut dedented = false;
+            while stack.last().is_some_and(|top| indent < *top) {
+                stack.pop();
+                dedented = true;
+            }
+            match stack.last() {
+                Some(top) if *top == indent => {}
+                _ if dedented => return Err(format!("inconsistent dedent to column {}", indent)),
+                _ => stack.push(indent),
+            }
+            Ok((stack.len() - 1, text))
+        })
+}
+
+#[test]
+fn test_indentation_stack() {
+    let parser = outline_line().many();
+    let input = "fruit\n  apple\n    green\n  pear\nveg\n  leek";
+    insta::assert_debug_snapshot!(run(&parser, Vec::new(), input), @r#"
+    (
+        Ok(
+            (
+                [
+                    (
+                        0,
+                        "fruit",
+                    ),
+                    (
+                        1,
+                        "apple",
+                    ),
+                    (
+                        2,
+                        "green",
+                    ),
+                    (
+                        1,
+                        "pear",
+                    ),
+                    (
+                        0,
+                        "veg",
+                    ),
+                    (
+                        1,
+                        "leek",
+                    ),
+                ],
+                "",
+            ),
+        ),
+        [
+            0,
+            2,
+        ],
+    )
+    "#);
+}
+
+#[test]
+fn test_inconsistent_dedent() {
+    let parser = outline_line().and(outline_line()).and(outline_line());
+    let (result, stack) = run(&parser, Vec::new(), "a\n    b\n  c");
+    insta::assert_debug_snapshot!((result, stack), @r#"
+    (
+        Err(
+            Message {
+                message: "inconsistent dedent to column 2",
+                input: "  c",
+            },
+        ),
+        [
+            0,
+        ],
+    )
+    "#);
+}
+
+#[test]
+fn test_stateful_tracks_declared_names() {
+    let declaration = stateful(|names: &mut Vec<String>, input| {
+        let (name, remaining) = lexeme(identifier()).parse(input)?;
+        if names.iter().any(|known| known == name) {
+            return Err(ParseError::message(
+                format!("'{}' declared twice", name),
+                input,
+            ));
+        }
+        names.push(name.to_string());
+        Ok((name, remaining))
+    });
+    let parser = declaration.many().map(|names| names.len());
+    insta::assert_debug_snapshot!(run(&parser, Vec::new(), "x y z y"), @r#"
+    (
+        Ok(
+            (
+                3,
+                "y",
+            ),
+        ),
+        [
+            "x",
+            "y",
+            "z",
+        ],
+    )
+    "#);
+}
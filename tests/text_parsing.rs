@@ -138,9 +138,9 @@ fn test_spaces1_failure() {
     insta::assert_debug_snapshot!(result, @r#"
     Err(
         Expected {
-            expected: "item satisfying predicate",
+            expected: "whitespace",
             found: Some(
-                "different item",
+                "'h'",
             ),
             input: "hello",
         },
@@ -218,6 +218,26 @@ fn test_integer_negative() {
     "###);
 }
 
+#[test]
+fn test_char_set_ascii_and_unicode() {
+    let parser = char_set("+-*/<>=!&|^~%é→").many1();
+    let result = parser.parse("<=→é+x");
+    insta::assert_debug_snapshot!(result, @r#"
+    Ok(
+        (
+            [
+                '<',
+                '=',
+                '→',
+                'é',
+                '+',
+            ],
+            "x",
+        ),
+    )
+    "#);
+}
+
 #[test]
 fn test_one_of_parser() {
     let parser = one_of("aeiou");
@@ -225,9 +245,9 @@ fn test_one_of_parser() {
     insta::assert_debug_snapshot!(result, @r#"
     Err(
         Expected {
-            expected: "item satisfying predicate",
+            expected: "one of \"aeiou\"",
             found: Some(
-                "different item",
+                "'h'",
             ),
             input: "hello",
         },
@@ -245,6 +265,78 @@ fn test_one_of_parser() {
     "###);
 }
 
+#[test]
+fn test_one_of_ascii_and_multibyte_members() {
+    // '\u{7f}' is the last ASCII char, '\u{80}' the first outside the bitmap
+    let parser = one_of("a\u{7f}é");
+    let results: Vec<_> = ["a", "\u{7f}", "é", "\u{80}", "ü"]
+        .into_iter()
+        .map(|source| parser.parse(source).map_err(|e| e.to_string()))
+        .collect();
+    insta::assert_debug_snapshot!(results, @r#"
+    [
+        Ok(
+            (
+                'a',
+                "",
+            ),
+        ),
+        Ok(
+            (
+                '\u{7f}',
+                "",
+            ),
+        ),
+        Ok(
+            (
+                'é',
+                "",
+            ),
+        ),
+        Err(
+            "expected one of \"a\\u{7f}é\", found '\\u{80}' at \"\\u{80}\"",
+        ),
+        Err(
+            "expected one of \"a\\u{7f}é\", found 'ü' at \"ü\"",
+        ),
+    ]
+    "#);
+}
+
+#[test]
+fn test_none_of_ascii_and_multibyte_members() {
+    let parser = none_of("a\u{7f}é");
+    let results: Vec<_> = ["a", "\u{7f}", "é", "\u{80}", "ü"]
+        .into_iter()
+        .map(|source| parser.parse(source).map_err(|e| e.to_string()))
+        .collect();
+    insta::assert_debug_snapshot!(results, @r#"
+    [
+        Err(
+            "expected none of \"a\\u{7f}é\", found 'a' at \"a\"",
+        ),
+        Err(
+            "expected none of \"a\\u{7f}é\", found '\\u{7f}' at \"\\u{7f}\"",
+        ),
+        Err(
+            "expected none of \"a\\u{7f}é\", found 'é' at \"é\"",
+        ),
+        Ok(
+            (
+                '\u{80}',
+                "",
+            ),
+        ),
+        Ok(
+            (
+                'ü',
+                "",
+            ),
+        ),
+    ]
+    "#);
+}
+
 #[test]
 fn test_none_of_parser() {
     let parser = none_of("aeiou");
@@ -257,6 +349,29 @@ fn test_none_of_parser() {
         ),
     )
     "###);
+
+    let result2 = parser.parse("éa");
+    insta::assert_debug_snapshot!(result2, @r#"
+    Ok(
+        (
+            'é',
+            "a",
+        ),
+    )
+    "#);
+
+    let result3 = parser.parse("apple");
+    insta::assert_debug_snapshot!(result3, @r#"
+    Err(
+        Expected {
+            expected: "none of \"aeiou\"",
+            found: Some(
+                "'a'",
+            ),
+            input: "apple",
+        },
+    )
+    "#);
 }
 
 #[test]
@@ -276,12 +391,1385 @@ fn test_not_char_parser() {
     insta::assert_debug_snapshot!(result2, @r#"
     Err(
         Expected {
-            expected: "item satisfying predicate",
+            expected: "any character except 'x'",
             found: Some(
-                "different item",
+                "'x'",
             ),
             input: "xhello",
         },
     )
     "#);
 }
+
+#[test]
+fn test_take_while_parser() {
+    let parser = take_while(|c: &char| c.is_ascii_digit());
+    let result = parser.parse("123abc");
+    insta::assert_debug_snapshot!(result, @r#"
+    Ok(
+        (
+            "123",
+            "abc",
+        ),
+    )
+    "#);
+}
+
+#[test]
+fn test_fill_while_reuses_buffer() {
+    let buffer = std::cell::RefCell::new(String::with_capacity(16));
+    let field = fill_while(|c: &char| *c != ',', &buffer);
+    let mut input = "alpha,beta,gamma";
+    let mut fields = Vec::new();
+    let capacity = buffer.borrow().capacity();
+    loop {
+        buffer.borrow_mut().clear();
+        let (_, rest) = field.parse(input).unwrap();
+        fields.push(buffer.borrow().clone());
+        match char(',').parse(rest) {
+            Ok((_, rest)) => input = rest,
+            Err(_) => break,
+        }
+    }
+    assert_eq!(buffer.borrow().capacity(), capacity);
+    insta::assert_debug_snapshot!(fields, @r#"
+    [
+        "alpha",
+        "beta",
+        "gamma",
+    ]
+    "#);
+}
+
+#[test]
+fn test_fill_while_predicate_reads_buffer() {
+    let buffer = std::cell::RefCell::new(String::new());
+    let field = fill_while(|_: &char| buffer.borrow().len() < 3, &buffer);
+    let result = field.parse("abcdef");
+    insta::assert_debug_snapshot!((result, buffer.borrow().as_str()), @r#"
+    (
+        Ok(
+            (
+                3,
+                "def",
+            ),
+        ),
+        "abc",
+    )
+    "#);
+}
+
+#[test]
+fn test_take_while1_failure() {
+    let parser = take_while1(|c: &char| c.is_ascii_digit());
+    let result = parser.parse("abc");
+    insta::assert_debug_snapshot!(result, @r#"
+    Err(
+        Expected {
+            expected: "item satisfying predicate",
+            found: Some(
+                "'a'",
+            ),
+            input: "abc",
+        },
+    )
+    "#);
+}
+
+#[test]
+fn test_eol_prefers_crlf() {
+    let parser = eol();
+    let result = parser.parse("\r\nnext");
+    insta::assert_debug_snapshot!(result, @r#"
+    Ok(
+        (
+            "\r\n",
+            "next",
+        ),
+    )
+    "#);
+}
+
+#[test]
+fn test_eol_failure() {
+    let parser = eol();
+    let result = parser.parse("\rnext");
+    insta::assert_debug_snapshot!(result, @r#"
+    Err(
+        Expected {
+            expected: "end of line",
+            found: Some(
+                "character '\r'",
+            ),
+            input: "\rnext",
+        },
+    )
+    "#);
+}
+
+#[test]
+fn test_line_parser() {
+    let parser = line().many();
+    let result = parser.parse("first\r\nsecond\nlast");
+    insta::assert_debug_snapshot!(result, @r#"
+    Ok(
+        (
+            [
+                "first",
+                "second",
+                "last",
+            ],
+            "",
+        ),
+    )
+    "#);
+}
+
+#[test]
+fn test_lines_table_skip_blank() {
+    let row = sep_by(unsigned(), spaces1());
+    let parser = lines(row).skip_blank();
+    let result = parser.parse("1 2 3\n\n  \r\n4 5\n");
+    insta::assert_debug_snapshot!(result, @r#"
+    Ok(
+        (
+            [
+                [
+                    1,
+                    2,
+                    3,
+                ],
+                [
+                    4,
+                    5,
+                ],
+            ],
+            "",
+        ),
+    )
+    "#);
+}
+
+#[test]
+fn test_lines_error_points_into_input() {
+    let row = sep_by(unsigned(), spaces1());
+    let result = lines(row).parse("1 2\n3 x\n");
+    insta::assert_debug_snapshot!(result, @r#"
+    Err(
+        Message {
+            message: "unexpected trailing input",
+            input: " x\n",
+        },
+    )
+    "#);
+}
+
+#[test]
+fn test_lines_error_outside_the_line() {
+    let foreign = fail::<&str, ()>("bad row").map_err(|_| ParseError::message("bad row", "x"));
+    let short = lines(char('a'));
+    insta::assert_debug_snapshot!((lines(foreign).parse("aé\n"), short.parse("a\n\n")), @r#"
+    (
+        Err(
+            Message {
+                message: "bad row",
+                input: "aé\n",
+            },
+        ),
+        Err(
+            Message {
+                message: "unexpected end of line",
+                input: "\n",
+            },
+        ),
+    )
+    "#);
+}
+
+#[test]
+fn test_lines_trailing_input_outside_the_line() {
+    // A row parser that hands back a leftover of its own
+    struct Foreign;
+
+    impl<'a> Parser<&'a str, ()> for Foreign {
+        fn parse(&self, _input: &'a str) -> ParseResult<&'a str, ()> {
+            Ok(((), "z"))
+        }
+    }
+
+    insta::assert_debug_snapshot!(lines(Foreign).parse("aé\n"), @r#"
+    Err(
+        Message {
+            message: "unexpected trailing input",
+            input: "z",
+        },
+    )
+    "#);
+}
+
+#[test]
+fn test_identifier_parser() {
+    let parser = identifier();
+    let result = parser.parse("_foo42 = 1");
+    insta::assert_debug_snapshot!(result, @r#"
+    Ok(
+        (
+            "_foo42",
+            " = 1",
+        ),
+    )
+    "#);
+}
+
+#[test]
+fn test_identifier_leading_digit() {
+    let parser = identifier();
+    let result = parser.parse("1foo");
+    insta::assert_debug_snapshot!(result, @r#"
+    Err(
+        Expected {
+            expected: "identifier",
+            found: Some(
+                "character '1'",
+            ),
+            input: "1foo",
+        },
+    )
+    "#);
+}
+
+#[test]
+fn test_identifier_with_custom_rules() {
+    let parser = identifier_with(
+        |c: &char| c.is_ascii_lowercase(),
+        |c: &char| c.is_ascii_alphanumeric() || *c == '-',
+    );
+    let result = parser.parse("kebab-case-name rest");
+    insta::assert_debug_snapshot!(result, @r#"
+    Ok(
+        (
+            "kebab-case-name",
+            " rest",
+        ),
+    )
+    "#);
+}
+
+#[test]
+fn test_symbol_parser() {
+    let parser = symbol("let");
+    let result = parser.parse("let \t x");
+    insta::assert_debug_snapshot!(result, @r#"
+    Ok(
+        (
+            "let",
+            "x",
+        ),
+    )
+    "#);
+}
+
+#[test]
+fn test_boolean_parser() {
+    let parser = boolean().skip(char(',')).and(boolean());
+    let result = parser.parse("true,false");
+    insta::assert_debug_snapshot!(result, @r#"
+    Ok(
+        (
+            (
+                true,
+                false,
+            ),
+            "",
+        ),
+    )
+    "#);
+}
+
+#[test]
+fn test_boolean_rejects_longer_word() {
+    let parser = boolean();
+    let result = parser.parse("truex");
+    insta::assert_debug_snapshot!(result, @r#"
+    Err(
+        Many(
+            [
+                Expected {
+                    expected: "keyword 'true'",
+                    found: Some(
+                        "character 'x'",
+                    ),
+                    input: "truex",
+                },
+                Expected {
+                    expected: "keyword 'false'",
+                    found: Some(
+                        "character 't'",
+                    ),
+                    input: "truex",
+                },
+            ],
+        ),
+    )
+    "#);
+}
+
+#[test]
+fn test_null_parser() {
+    let parser = null();
+    let result = parser.parse("null]");
+    insta::assert_debug_snapshot!(result, @r#"
+    Ok(
+        (
+            (),
+            "]",
+        ),
+    )
+    "#);
+}
+
+#[test]
+fn test_keyword_parser() {
+    let parser = keyword("let");
+    let result = parser.parse("let x");
+    insta::assert_debug_snapshot!(result, @r#"
+    Ok(
+        (
+            "let",
+            " x",
+        ),
+    )
+    "#);
+}
+
+#[test]
+fn test_keyword_boundary_violation() {
+    let parser = keyword("true");
+    let result = parser.parse("truelove");
+    insta::assert_debug_snapshot!(result, @r#"
+    Err(
+        Expected {
+            expected: "keyword 'true'",
+            found: Some(
+                "character 'l'",
+            ),
+            input: "truelove",
+        },
+    )
+    "#);
+}
+
+#[test]
+fn test_keyword_mismatch() {
+    let parser = keyword("let");
+    let result = parser.parse("lex");
+    insta::assert_debug_snapshot!(result, @r#"
+    Err(
+        Expected {
+            expected: "keyword 'let'",
+            found: Some(
+                "character 'x'",
+            ),
+            input: "lex",
+        },
+    )
+    "#);
+}
+
+#[test]
+fn test_keywords_macro() {
+    #[derive(Debug, Clone)]
+    enum Type {
+        In,
+        Int,
+        Integer,
+    }
+    let parser = keywords! { "in" => Type::In, "int" => Type::Int, "integer" => Type::Integer };
+    let words = sep_by(&parser, char(' '));
+    insta::assert_debug_snapshot!((words.parse("int in integer"), parser.parse("inty")), @r#"
+    (
+        Ok(
+            (
+                [
+                    Int,
+                    In,
+                    Integer,
+                ],
+                "",
+            ),
+        ),
+        Err(
+            Expected {
+                expected: "one of keywords 'integer', 'int', 'in'",
+                found: Some(
+                    "character 'i'",
+                ),
+                input: "inty",
+            },
+        ),
+    )
+    "#);
+}
+
+#[test]
+fn test_alt_mixed_parser_types() {
+    let parser = alt((
+        string("yes").map(|_| true),
+        char('y').map(|_| true),
+        keyword("no").map(|_| false),
+    ));
+    let result = parser.many().parse("yesyno");
+    insta::assert_debug_snapshot!(result, @r#"
+    Ok(
+        (
+            [
+                true,
+                true,
+                false,
+            ],
+            "",
+        ),
+    )
+    "#);
+}
+
+#[test]
+fn test_alt_all_fail() {
+    let parser = alt((string("ab").map(|_| ()), char('c').map(|_| ())));
+    let result = parser.parse("x");
+    insta::assert_debug_snapshot!(result, @r#"
+    Err(
+        Many(
+            [
+                Expected {
+                    expected: "string 'ab'",
+                    found: Some(
+                        "character 'x'",
+                    ),
+                    input: "x",
+                },
+                Expected {
+                    expected: "'c'",
+                    found: Some(
+                        "'x'",
+                    ),
+                    input: "x",
+                },
+            ],
+        ),
+    )
+    "#);
+}
+
+#[test]
+fn test_sep_by_with_sep_evaluates_expression() {
+    let parser = sep_by_with_sep(unsigned(), char('+').or(char('-'))).map(|(first, rest)| {
+        rest.into_iter()
+            .fold(first as i64, |acc, (op, n)| match op {
+                '+' => acc + n as i64,
+                _ => acc - n as i64,
+            })
+    });
+    let result = parser.parse("10+5-3+");
+    insta::assert_debug_snapshot!(result, @r#"
+    Ok(
+        (
+            12,
+            "+",
+        ),
+    )
+    "#);
+}
+
+#[test]
+fn test_sep_by_with_sep_requires_first_item() {
+    let parser = sep_by_with_sep(unsigned(), char(','));
+    let result = parser.parse(",1");
+    insta::assert_debug_snapshot!(result, @r#"
+    Err(
+        Expected {
+            expected: "digit",
+            found: Some(
+                "','",
+            ),
+            input: ",1",
+        },
+    )
+    "#);
+}
+
+#[test]
+fn test_map_into_newtype() {
+    struct Port(u32);
+
+    impl From<u32> for Port {
+        fn from(n: u32) -> Self {
+            Port(n)
+        }
+    }
+
+    let parser = unsigned().map_into::<Port>();
+    let result = parser
+        .parse("8080")
+        .map(|(port, remaining)| (port.0, remaining));
+    insta::assert_debug_snapshot!(result, @r#"
+    Ok(
+        (
+            8080,
+            "",
+        ),
+    )
+    "#);
+}
+
+#[test]
+fn test_map_into_widening() {
+    let parser = unsigned().map_into::<u64>().map(|n| n * 2);
+    let result = parser.parse("4294967295");
+    insta::assert_debug_snapshot!(result, @r#"
+    Ok(
+        (
+            8589934590,
+            "",
+        ),
+    )
+    "#);
+}
+
+#[test]
+fn test_flatten_nested_option() {
+    let sign = char('-').optional().optional().flatten();
+    let result = sign.parse("-5");
+    insta::assert_debug_snapshot!(result, @r#"
+    Ok(
+        (
+            Some(
+                '-',
+            ),
+            "5",
+        ),
+    )
+    "#);
+}
+
+#[test]
+fn test_flatten_result_success() {
+    let parser = unsigned()
+        .map(|n| u8::try_from(n).map_err(|_| format!("{} does not fit in a byte", n)))
+        .flatten_result();
+    let result = parser.parse("200,");
+    insta::assert_debug_snapshot!(result, @r#"
+    Ok(
+        (
+            200,
+            ",",
+        ),
+    )
+    "#);
+}
+
+#[test]
+fn test_flatten_result_failure() {
+    let parser = unsigned()
+        .map(|n| u8::try_from(n).map_err(|_| format!("{} does not fit in a byte", n)))
+        .flatten_result();
+    let result = parser.parse("300,");
+    insta::assert_debug_snapshot!(result, @r#"
+    Err(
+        Message {
+            message: "300 does not fit in a byte",
+            input: "300,",
+        },
+    )
+    "#);
+}
+
+#[test]
+fn test_verify_map_success() {
+    let parser = identifier().verify_map(|name| match name {
+        "true" => Ok(true),
+        "false" => Ok(false),
+        other => Err(format!("'{}' is not a boolean", other)),
+    });
+    let result = parser.parse("false)");
+    insta::assert_debug_snapshot!(result, @r#"
+    Ok(
+        (
+            false,
+            ")",
+        ),
+    )
+    "#);
+}
+
+#[test]
+fn test_verify_map_reports_at_value() {
+    let parser = unsigned().verify_map(|n| {
+        if n % 2 == 0 {
+            Ok(n / 2)
+        } else {
+            Err(format!("{} is odd", n))
+        }
+    });
+    let result = parser.parse("17 rest");
+    insta::assert_debug_snapshot!(result, @r#"
+    Err(
+        Message {
+            message: "17 is odd",
+            input: "17 rest",
+        },
+    )
+    "#);
+}
+
+#[test]
+fn test_map_res_reports_at_token() {
+    let byte = take_while1(|c: &char| c.is_ascii_digit()).map_res(str::parse::<u8>);
+    let parser = string("port=").preceded_by(spaces()).and(byte);
+    let result = parser.parse("  port=300;");
+    insta::assert_debug_snapshot!(result, @r#"
+    Err(
+        Message {
+            message: "number too large to fit in target type",
+            input: "300;",
+        },
+    )
+    "#);
+}
+
+#[test]
+fn test_map_if_percentage_suffix() {
+    let amount = unsigned().map_if(char('%'), |n| n as f64 / 100.0, |n| n as f64);
+    let parser = amount.and(char('%').optional());
+    insta::assert_debug_snapshot!((parser.parse("50%"), parser.parse("50")), @r#"
+    (
+        Ok(
+            (
+                (
+                    0.5,
+                    Some(
+                        '%',
+                    ),
+                ),
+                "",
+            ),
+        ),
+        Ok(
+            (
+                (
+                    50.0,
+                    None,
+                ),
+                "",
+            ),
+        ),
+    )
+    "#);
+}
+
+#[test]
+fn test_preceded_terminated_delimited() {
+    let key = terminated(identifier(), char('='));
+    let value = delimited(char('"'), take_while(|c| *c != '"'), char('"'));
+    let parser = preceded(spaces(), key.and(value));
+    let result = parser.parse("  name=\"simon\";");
+    insta::assert_debug_snapshot!(result, @r#"
+    Ok(
+        (
+            (
+                "name",
+                "simon",
+            ),
+            ";",
+        ),
+    )
+    "#);
+}
+
+#[test]
+fn test_not_followed_by_maximal_munch() {
+    let assign = char('=').not_followed_by(char('=')).map(|_| "assign");
+    let equals = string("==").map(|_| "equals");
+    let parser = assign.or(equals).skip(spaces()).many();
+    let result = parser.parse("= == =");
+    insta::assert_debug_snapshot!(result, @r#"
+    Ok(
+        (
+            [
+                "assign",
+                "equals",
+                "assign",
+            ],
+            "",
+        ),
+    )
+    "#);
+}
+
+#[test]
+fn test_not_followed_by_failure() {
+    let parser = char('<').not_followed_by(char('='));
+    let result = parser.parse("<=");
+    insta::assert_debug_snapshot!(result, @r#"
+    Err(
+        Message {
+            message: "unexpected input",
+            input: "=",
+        },
+    )
+    "#);
+}
+
+#[test]
+fn test_followed_by_leaves_suffix() {
+    let parser = unsigned().followed_by(string("px")).and(string("px"));
+    let result = parser.parse("12px");
+    insta::assert_debug_snapshot!(result, @r#"
+    Ok(
+        (
+            (
+                12,
+                "px",
+            ),
+            "",
+        ),
+    )
+    "#);
+}
+
+#[test]
+fn test_followed_by_failure() {
+    let parser = unsigned().followed_by(string("px"));
+    let result = parser.parse("12em");
+    insta::assert_debug_snapshot!(result, @r#"
+    Err(
+        Expected {
+            expected: "string 'px'",
+            found: Some(
+                "character 'e'",
+            ),
+            input: "em",
+        },
+    )
+    "#);
+}
+
+#[test]
+fn test_peek_does_not_consume() {
+    let parser = peek(identifier()).and(rest());
+    let result = parser.parse("abc def");
+    insta::assert_debug_snapshot!(result, @r#"
+    Ok(
+        (
+            (
+                "abc",
+                "abc def",
+            ),
+            "",
+        ),
+    )
+    "#);
+}
+
+#[test]
+fn test_or_default() {
+    let sign = char('-').map(|_| -1).or(char('+').map(|_| 1)).or_default(1);
+    let parser = sign.and(unsigned()).map(|(sign, n)| sign * n as i64);
+    let result = parser.parse("42");
+    insta::assert_debug_snapshot!(result, @r#"
+    Ok(
+        (
+            42,
+            "",
+        ),
+    )
+    "#);
+}
+
+#[test]
+fn test_map_with_input_keeps_spelling() {
+    let parser = integer().map_with_input(|value, text: &str| (value, text.to_string()));
+    let result = parser.parse("-0042,");
+    insta::assert_debug_snapshot!(result, @r#"
+    Ok(
+        (
+            (
+                -42,
+                "-0042",
+            ),
+            ",",
+        ),
+    )
+    "#);
+}
+
+#[test]
+fn test_map_with_input_multibyte() {
+    let parser = alpha().many1().map_with_input(|_, text: &str| text);
+    let result = parser.parse("héllo wörld");
+    insta::assert_debug_snapshot!(result, @r#"
+    Ok(
+        (
+            "héllo",
+            " wörld",
+        ),
+    )
+    "#);
+}
+
+#[test]
+fn test_unsigned_grouped() {
+    let parser = unsigned_grouped();
+    let result = parser.parse("1_000_000 rest");
+    insta::assert_debug_snapshot!(result, @r#"
+    Ok(
+        (
+            1000000,
+            " rest",
+        ),
+    )
+    "#);
+}
+
+#[test]
+fn test_unsigned_grouped_doubled_underscore() {
+    let parser = unsigned_grouped();
+    let result = parser.parse("1__000");
+    insta::assert_debug_snapshot!(result, @r#"
+    Err(
+        Message {
+            message: "misplaced digit separator '_'",
+            input: "1__000",
+        },
+    )
+    "#);
+}
+
+#[test]
+fn test_unsigned_grouped_trailing_underscore() {
+    let parser = unsigned_grouped();
+    let result = parser.parse("1000_");
+    insta::assert_debug_snapshot!(result, @r#"
+    Err(
+        Message {
+            message: "misplaced digit separator '_'",
+            input: "1000_",
+        },
+    )
+    "#);
+}
+
+#[test]
+fn test_integer_grouped_negative() {
+    let parser = integer_grouped();
+    let result = parser.parse("-12_345");
+    insta::assert_debug_snapshot!(result, @r#"
+    Ok(
+        (
+            -12345,
+            "",
+        ),
+    )
+    "#);
+}
+
+#[test]
+fn test_integer_grouped_min() {
+    let parser = integer_grouped();
+    let result = parser.parse("-2_147_483_648");
+    insta::assert_debug_snapshot!(result, @r#"
+    Ok(
+        (
+            -2147483648,
+            "",
+        ),
+    )
+    "#);
+}
+
+#[test]
+fn test_integer_grouped_out_of_range() {
+    let parser = integer_grouped();
+    let result = parser.parse("3_000_000_000 ");
+    insta::assert_debug_snapshot!(result, @r#"
+    Err(
+        Message {
+            message: "integer out of range",
+            input: "3_000_000_000 ",
+        },
+    )
+    "#);
+}
+
+#[test]
+fn test_unsigned_grouped_not_a_number() {
+    let parser = unsigned_grouped();
+    let result = parser.parse("x1");
+    insta::assert_debug_snapshot!(result, @r#"
+    Err(
+        Expected {
+            expected: "digit",
+            found: Some(
+                "character 'x'",
+            ),
+            input: "x1",
+        },
+    )
+    "#);
+}
+
+#[test]
+fn test_float_forms() {
+    let parser = float().skip(spaces()).many();
+    let result = parser.parse("1 -2.5 .5 +3. 6.02e23 1e-3 7e");
+    insta::assert_debug_snapshot!(result, @r#"
+    Ok(
+        (
+            [
+                1.0,
+                -2.5,
+                0.5,
+                3.0,
+                6.02e23,
+                0.001,
+                7.0,
+            ],
+            "e",
+        ),
+    )
+    "#);
+}
+
+#[test]
+fn test_decimal_parts_keeps_digits() {
+    let parser = decimal_parts().skip(spaces()).many();
+    let result = parser.parse("-123.4500e-7 .5 +10");
+    insta::assert_debug_snapshot!(result, @r#"
+    Ok(
+        (
+            [
+                DecimalParts {
+                    sign: Some(
+                        '-',
+                    ),
+                    int_digits: "123",
+                    frac_digits: "4500",
+                    exp: Some(
+                        -7,
+                    ),
+                },
+                DecimalParts {
+                    sign: None,
+                    int_digits: "",
+                    frac_digits: "5",
+                    exp: None,
+                },
+                DecimalParts {
+                    sign: Some(
+                        '+',
+                    ),
+                    int_digits: "10",
+                    frac_digits: "",
+                    exp: None,
+                },
+            ],
+            "",
+        ),
+    )
+    "#);
+}
+
+#[test]
+fn test_decimal_parts_exponent_out_of_range() {
+    let parser = decimal_parts();
+    let result = parser.parse("1e99999999999");
+    insta::assert_debug_snapshot!(result, @r#"
+    Err(
+        Message {
+            message: "exponent out of range",
+            input: "1e99999999999",
+        },
+    )
+    "#);
+}
+
+#[test]
+fn test_float_rejects_inf_by_default() {
+    let parser = float();
+    let result = parser.parse("inf");
+    insta::assert_debug_snapshot!(result, @r#"
+    Err(
+        Expected {
+            expected: "number",
+            found: Some(
+                "character 'i'",
+            ),
+            input: "inf",
+        },
+    )
+    "#);
+}
+
+#[test]
+fn test_float_overflow_needs_allow_inf() {
+    let allow_inf = float_opts(FloatOptions {
+        allow_inf: true,
+        ..FloatOptions::default()
+    });
+    insta::assert_debug_snapshot!((float().parse("1e400"), allow_inf.parse("-1e400")), @r#"
+    (
+        Err(
+            Message {
+                message: "number out of range",
+                input: "1e400",
+            },
+        ),
+        Ok(
+            (
+                -inf,
+                "",
+            ),
+        ),
+    )
+    "#);
+}
+
+#[test]
+fn test_float_opts_allow_inf_and_nan() {
+    let options = FloatOptions {
+        allow_inf: true,
+        allow_nan: true,
+        ..FloatOptions::default()
+    };
+    let parser = float_opts(options).skip(spaces()).many();
+    let result = parser.parse("-Infinity inf NaN");
+    insta::assert_debug_snapshot!(result, @r#"
+    Ok(
+        (
+            [
+                -inf,
+                inf,
+                NaN,
+            ],
+            "",
+        ),
+    )
+    "#);
+}
+
+#[test]
+fn test_float_opts_require_fraction() {
+    let options = FloatOptions {
+        require_fraction: true,
+        ..FloatOptions::default()
+    };
+    let parser = float_opts(options);
+    let result = parser.parse("42");
+    insta::assert_debug_snapshot!(result, @r#"
+    Err(
+        Message {
+            message: "expected fractional part",
+            input: "42",
+        },
+    )
+    "#);
+}
+
+#[test]
+fn test_sep_by_m_n_stops_at_max() {
+    let parser = sep_by_m_n(3, 5, integer(), char(','));
+    let result = parser.parse("1,2,3,4,5,6");
+    insta::assert_debug_snapshot!(result, @r#"
+    Ok(
+        (
+            [
+                1,
+                2,
+                3,
+                4,
+                5,
+            ],
+            ",6",
+        ),
+    )
+    "#);
+}
+
+#[test]
+fn test_sep_by_m_n_too_few() {
+    let parser = sep_by_m_n(3, 5, integer(), char(','));
+    let result = parser.parse("1,2,x");
+    insta::assert_debug_snapshot!(result, @r#"
+    Err(
+        Message {
+            message: "expected at least 3 items, found 2",
+            input: "1,2,x",
+        },
+    )
+    "#);
+}
+
+#[test]
+fn test_sep_by_m_n_zero_min() {
+    let parser = sep_by_m_n(0, 2, integer(), char(','));
+    let result = parser.parse("x");
+    insta::assert_debug_snapshot!(result, @r#"
+    Ok(
+        (
+            [],
+            "x",
+        ),
+    )
+    "#);
+}
+
+#[test]
+fn test_count_sep_ipv4_leaves_trailing_separator() {
+    let parser = count_sep(4, unsigned(), char('.'));
+    let result = parser.parse("192.168.0.1.");
+    insta::assert_debug_snapshot!(result, @r#"
+    Ok(
+        (
+            [
+                192,
+                168,
+                0,
+                1,
+            ],
+            ".",
+        ),
+    )
+    "#);
+}
+
+#[test]
+fn test_count_sep_too_few() {
+    let parser = count_sep(4, unsigned(), char('.'));
+    let result = parser.parse("10.0.1");
+    insta::assert_debug_snapshot!(result, @r#"
+    Err(
+        Message {
+            message: "expected at least 4 items, found 3",
+            input: "10.0.1",
+        },
+    )
+    "#);
+}
+
+#[test]
+fn test_longest_prefers_maximal_munch() {
+    let operator = longest(vec![string("<"), string("<="), string("<<")]);
+    let parser = operator.skip(spaces()).many();
+    let result = parser.parse("<= < <<");
+    insta::assert_debug_snapshot!(result, @r#"
+    Ok(
+        (
+            [
+                "<=",
+                "<",
+                "<<",
+            ],
+            "",
+        ),
+    )
+    "#);
+}
+
+#[test]
+fn test_choice_indexed() {
+    let parser = choice_indexed(vec![keyword("let"), keyword("const"), keyword("var")]);
+    let result = parser.parse("const x");
+    insta::assert_debug_snapshot!(result, @r#"
+    Ok(
+        (
+            (
+                1,
+                "const",
+            ),
+            " x",
+        ),
+    )
+    "#);
+}
+
+#[test]
+fn test_skip_ws_and_comments() {
+    let parser = skip_ws_and_comments("//", ("/*", "*/")).preceded_by(char('x'));
+    let result = parser.parse("x // note\n  /* block\n */ y");
+    insta::assert_debug_snapshot!(result, @r#"
+    Ok(
+        (
+            (),
+            "y",
+        ),
+    )
+    "#);
+}
+
+#[test]
+fn test_skip_ws_and_comments_nested() {
+    let flat = skip_ws_and_comments("--", ("{-", "-}"));
+    let nested = skip_ws_and_comments("--", ("{-", "-}")).nested();
+    let input = "{- a {- b -} c -} d";
+    insta::assert_debug_snapshot!((flat.parse(input), nested.parse(input)), @r#"
+    (
+        Ok(
+            (
+                (),
+                "c -} d",
+            ),
+        ),
+        Ok(
+            (
+                (),
+                "d",
+            ),
+        ),
+    )
+    "#);
+}
+
+#[test]
+fn test_skip_ws_and_comments_line_only_and_unterminated() {
+    let line_only = skip_ws_and_comments("#", ("", ""));
+    let unterminated = skip_ws_and_comments("//", ("/*", "*/"));
+    insta::assert_debug_snapshot!((line_only.parse(" # only a comment"), unterminated.parse("/* open")), @r#"
+    (
+        Ok(
+            (
+                (),
+                "",
+            ),
+        ),
+        Err(
+            Incomplete {
+                needed: None,
+            },
+        ),
+    )
+    "#);
+}
+
+#[test]
+fn test_between_balanced() {
+    let parser = between_balanced('(', ')');
+    let result = parser.parse("(a (b (c)) d) rest");
+    insta::assert_debug_snapshot!(result, @r#"
+    Ok(
+        (
+            "a (b (c)) d",
+            " rest",
+        ),
+    )
+    "#);
+}
+
+#[test]
+fn test_between_balanced_unbalanced() {
+    let parser = between_balanced('[', ']');
+    let results = (parser.parse("[a [b"), parser.parse("x[]"));
+    insta::assert_debug_snapshot!(results, @r#"
+    (
+        Err(
+            Incomplete {
+                needed: Some(
+                    2,
+                ),
+            },
+        ),
+        Err(
+            Expected {
+                expected: "'['",
+                found: Some(
+                    "'x'",
+                ),
+                input: "x[]",
+            },
+        ),
+    )
+    "#);
+}
+
+#[test]
+fn test_json_number_kinds() {
+    let parser = json_number();
+    let results: Vec<_> = ["-12,", "0.5]", "1e3", "2.", "-0"]
+        .into_iter()
+        .map(|input| parser.parse(input))
+        .collect();
+    insta::assert_debug_snapshot!(results, @r#"
+    [
+        Ok(
+            (
+                Int(
+                    -12,
+                ),
+                ",",
+            ),
+        ),
+        Ok(
+            (
+                Float(
+                    0.5,
+                ),
+                "]",
+            ),
+        ),
+        Ok(
+            (
+                Float(
+                    1000.0,
+                ),
+                "",
+            ),
+        ),
+        Ok(
+            (
+                Int(
+                    2,
+                ),
+                ".",
+            ),
+        ),
+        Ok(
+            (
+                Int(
+                    0,
+                ),
+                "",
+            ),
+        ),
+    ]
+    "#);
+}
+
+#[test]
+fn test_json_number_errors() {
+    let parser = json_number();
+    let lenient = json_number_opts(JsonNumberOptions {
+        allow_leading_zeros: true,
+    });
+    let results = (
+        parser.parse("007").unwrap_err().to_string(),
+        lenient.parse("007"),
+        parser.parse("9223372036854775808").unwrap_err().to_string(),
+        parser.parse("1e400").unwrap_err().to_string(),
+        parser.parse("+1").unwrap_err().to_string(),
+    );
+    insta::assert_debug_snapshot!(results, @r#"
+    (
+        "leading zeros are not allowed at \"007\"",
+        Ok(
+            (
+                Int(
+                    7,
+                ),
+                "",
+            ),
+        ),
+        "number out of range at \"9223372036854775808\"",
+        "number out of range at \"1e400\"",
+        "expected number, found character '+' at \"+1\"",
+    )
+    "#);
+}